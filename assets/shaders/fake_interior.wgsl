@@ -1,7 +1,9 @@
 #import bevy_pbr::{
   pbr_fragment::pbr_input_from_standard_material,
   pbr_functions::alpha_discard,
-  mesh_view_bindings::view,
+  mesh_view_bindings::{view, lights},
+  pbr_bindings,
+  pbr_types,
   pbr_bindings::{
     base_color_texture,
     base_color_sampler,
@@ -9,16 +11,21 @@
 }
 
 #ifdef PREPASS_PIPELINE
-#import bevy_pbr::{
-  prepass_io::{VertexOutput, FragmentOutput},
-  pbr_deferred_functions::deferred_output,
-}
+#import bevy_pbr::prepass_io::{VertexOutput, FragmentOutput}
+#ifdef DEFERRED_PREPASS
+#import bevy_pbr::pbr_deferred_functions::deferred_output
+#else
+#import bevy_pbr::pbr_prepass_functions::calculate_motion_vector
+#endif
 #else
 #import bevy_pbr::{
   forward_io::{VertexOutput, FragmentOutput},
-	pbr_functions,
+  pbr_functions,
   pbr_functions::{apply_pbr_lighting, main_pass_post_lighting_processing},
   pbr_types::STANDARD_MATERIAL_FLAGS_UNLIT_BIT,
+  mesh_types::MESH_FLAGS_SHADOW_RECEIVER_BIT,
+  mesh_view_types::DIRECTIONAL_LIGHT_FLAGS_SHADOWS_ENABLED_BIT,
+  shadows,
 }
 #endif
 
@@ -26,16 +33,190 @@
 #import bevy_pbr::meshlet_visibility_buffer_resolve::resolve_vertex_output
 #endif
 
+// only set by the plugin for the pipelines whose view layout binds `globals`.
+#ifdef FAKE_INTERIOR_GLOBALS
+#import bevy_pbr::mesh_view_bindings::globals
+#endif
+
+#import bevy_pbr::mesh_bindings::mesh
+#import bevy_render::maths::affine3_to_square
+
 struct FakeInteriorMaterial {
   atlas_rooms: vec2<f32>,
   rooms: vec2<f32>,
   depth: f32,
   room_seed: f32,
-  light_seed: f32,
-  light_threshold: f32,
+  emission_seed: f32,
+  emission_threshold: f32,
+  depth_world: f32,
+  use_depth_world: u32,
+  uv_tiling: vec2<f32>,
+  night_only: u32,
+  box_min: vec3<f32>,
+  box_max: vec3<f32>,
+  reveal_color: vec4<f32>,
+  reveal_width: f32,
+  rooms_jitter: vec2<f32>,
+  chromatic_aberration: f32,
+  double_sided_interior: u32,
+  room_aspect: vec2<f32>,
+  floor_gloss: f32,
+  far_window: f32,
+  far_window_color: vec4<f32>,
+  emissive_from_base_alpha: u32,
+  interior_ambient: vec4<f32>,
+  shade_box_faces: u32,
+  occupant_fps: f32,
+  occupant_frames: u32,
+  occupant_chance: f32,
+  back_wall_scale: vec2<f32>,
+  base_color_is_linear: u32,
+  frost_amount: f32,
+  frost_taps: u32,
+  depth_variation: f32,
+  solid_threshold: f32,
+  sun_penetration: f32,
+  room_vignette: f32,
+  mirror_tiling: u32,
+  reflect_environment: u32,
+  sky_top_color: vec4<f32>,
+  sky_bottom_color: vec4<f32>,
+  atlas_debug: u32,
+  emission_intensity: f32,
+  ceiling_light: f32,
+  interior_rotation: f32,
+  clip_at_edges: u32,
+  ao_affects_interior: f32,
+  color_levels: u32,
+  emissive_uv_offset: vec2<f32>,
+  glass_reflectance: f32,
+  world_seed_scale: f32,
+  emissive_layer: f32,
+  far_window_rect: vec4<f32>,
+  far_window_sky: u32,
+  use_normal_map: u32,
+  glass_anisotropy: f32,
+  glass_aniso_direction: vec2<f32>,
+  flicker_seed: f32,
+  receive_exterior_shadows: u32,
+  uv_island_rooms: u32,
+  tv_flicker: f32,
+  border_blend: f32,
+  near_clip: f32,
 };
 
 @group(2) @binding(100) var<uniform> material: FakeInteriorMaterial;
+// the optional textures are only declared by the pipelines of the materials that set them,
+// see `FakeInteriorMaterialKey`.
+#ifdef FAKE_INTERIOR_OCCUPANTS
+@group(2) @binding(102) var occupant_texture: texture_2d<f32>;
+@group(2) @binding(103) var occupant_sampler: sampler;
+#endif
+#ifdef FAKE_INTERIOR_LIGHT_PALETTE
+@group(2) @binding(104) var light_palette_texture: texture_2d<f32>;
+#endif
+#ifdef FAKE_INTERIOR_MULLION_MASK
+@group(2) @binding(105) var mullion_mask_texture: texture_2d<f32>;
+@group(2) @binding(106) var mullion_mask_sampler: sampler;
+#endif
+#ifdef FAKE_INTERIOR_ROOM_IMAGES
+@group(2) @binding(107) var room_images: texture_2d_array<f32>;
+@group(2) @binding(108) var room_images_sampler: sampler;
+#endif
+#ifdef FAKE_INTERIOR_ROOM_INDEX_MAP
+@group(2) @binding(109) var room_index_map: texture_2d<f32>;
+#endif
+#ifdef FAKE_INTERIOR_PLANAR_REFLECTION
+@group(2) @binding(110) var planar_reflection_texture: texture_2d<f32>;
+@group(2) @binding(111) var planar_reflection_sampler: sampler;
+#endif
+#ifdef FAKE_INTERIOR_EMISSIVE_LAYERS
+@group(2) @binding(112) var emissive_layers: texture_2d_array<f32>;
+@group(2) @binding(113) var emissive_layers_sampler: sampler;
+#endif
+
+// layer of `room_images` of the fragment's room.
+var<private> room_layer: u32;
+
+// Shading of the room box faces for `shade_box_faces`. Keep in sync with `src/trace.rs`.
+const SIDE_WALL_SHADE: f32 = 0.8;
+const CEILING_SHADE: f32 = 0.9;
+const FLOOR_SHADE: f32 = 0.7;
+// Smallest slope of the view ray into the room, so rays along the wall don't divide by zero,
+// and the largest `near_clip`. Keep in sync with `src/trace.rs`.
+const MIN_VIEW_SLOPE: f32 = 0.001;
+const MAX_NEAR_CLIP: f32 = 0.99;
+// brightness of the room in full `receive_exterior_shadows` shadow.
+const EXTERIOR_SHADOW_SHADE: f32 = 0.35;
+
+// Upper bound of `frost_taps`.
+const FROST_MAX_TAPS: u32 = 32u;
+const GOLDEN_ANGLE: f32 = 2.39996323;
+
+#ifdef FAKE_INTERIOR_INSTANCE_PARAMS
+// Keep in sync with `src/instance.rs`.
+const INSTANCE_MAX_PROBES: u32 = 8u;
+const INSTANCE_POSITION_SCALE: f32 = 64.0;
+const INSTANCE_FLAG_DEPTH: u32 = 1u;
+const INSTANCE_FLAG_ROOM_SEED: u32 = 2u;
+const INSTANCE_FLAG_UNPOWERED: u32 = 4u;
+
+struct FakeInteriorInstance {
+  // w = 1.0 marks a used slot.
+  position: vec4<f32>,
+  tint: vec4<f32>,
+  depth: f32,
+  room_seed: f32,
+  flags: u32,
+  padding: u32,
+};
+
+@group(2) @binding(101) var<storage, read> instances: array<FakeInteriorInstance>;
+
+fn instance_hash(position: vec3<f32>) -> u32 {
+  let q = bitcast<vec3<u32>>(vec3<i32>(floor(position * INSTANCE_POSITION_SCALE + 0.5)));
+  return (q.x * 73856093u) ^ (q.y * 19349663u) ^ (q.z * 83492791u);
+}
+
+// Find the per-instance parameters of a mesh, keyed by its world translation.
+fn find_instance(instance_index: u32) -> FakeInteriorInstance {
+  let position = instance_position(instance_index);
+  let mask = arrayLength(&instances) - 1u;
+  var slot = instance_hash(position) & mask;
+  for (var i = 0u; i < INSTANCE_MAX_PROBES; i += 1u) {
+    let entry = instances[slot];
+    // an empty slot ends the probe chain.
+    if (entry.position.w == 0.0) {
+      break;
+    }
+    if (all(abs(entry.position.xyz - position) < vec3<f32>(0.5 / INSTANCE_POSITION_SCALE))) {
+      return entry;
+    }
+    slot = (slot + 1u) & mask;
+  }
+  return FakeInteriorInstance(vec4<f32>(0.0), vec4<f32>(1.0), 0.0, 0.0, 0u, 0u);
+}
+#endif
+
+// view ray in the room box turned by `interior_rotation` around the up axis (y), so the
+// room's forward axis (-z) turns towards +x for positive angles.
+fn rotate_interior(dir: vec3<f32>) -> vec3<f32> {
+  let c = cos(material.interior_rotation);
+  let s = sin(material.interior_rotation);
+  return vec3<f32>(dir.x * c + dir.z * s, dir.y, dir.z * c - dir.x * s);
+}
+
+// how often the glow of the `tv_flicker` rooms changes.
+const TV_CUTS_PER_SECOND: f32 = 6.0;
+
+// seconds for the animations, frozen at 0 without the globals binding.
+fn interior_time() -> f32 {
+#ifdef FAKE_INTERIOR_GLOBALS
+  return globals.time;
+#else
+  return 0.0;
+#endif
+}
 
 fn random2D(s: f32) -> vec2<f32> {
   return fract(sin(s * vec2<f32>(12.9898,78.233)) * 43758.5453);
@@ -45,6 +226,232 @@ fn random1D(s: f32) -> f32 {
   return fract(sin(s * 12.9898) * 43758.5453);
 }
 
+// PCG integer hash, matches `FakeInteriorMaterial::lit_rooms` exactly on the CPU.
+fn pcg_hash(v: u32) -> u32 {
+  let state = v * 747796405u + 2891336453u;
+  let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+  return (word >> 22u) ^ word;
+}
+
+// Random 0..1 value of a room for a seed. Keep in sync with `src/lib.rs`.
+fn room_random(room_index: f32, seed: f32) -> f32 {
+  let h = pcg_hash(bitcast<u32>(i32(room_index)) ^ pcg_hash(bitcast<u32>(seed)));
+  return f32(h >> 8u) / 16777216.0;
+}
+
+// World translation of the mesh instance.
+fn instance_position(instance_index: u32) -> vec3<f32> {
+  return affine3_to_square(mesh[instance_index].world_from_local)[3].xyz;
+}
+
+// World-space change of the position along the surface's U and V directions, zero where the
+// UVs are degenerate.
+fn world_per_uv(world_position: vec3<f32>, uv: vec2<f32>) -> mat2x3<f32> {
+  let dp_dx = dpdx(world_position);
+  let dp_dy = dpdy(world_position);
+  let duv_dx = dpdx(uv);
+  let duv_dy = dpdy(uv);
+  let det = duv_dx.x * duv_dy.y - duv_dx.y * duv_dy.x;
+  if (abs(det) < 1e-12) {
+    return mat2x3<f32>(vec3<f32>(0.0), vec3<f32>(0.0));
+  }
+  let dp_du = (dp_dx * duv_dy.y - dp_dy * duv_dx.y) / det;
+  let dp_dv = (dp_dy * duv_dx.x - dp_dx * duv_dy.x) / det;
+  return mat2x3<f32>(dp_du, dp_dv);
+}
+
+// World-space length of one UV unit along the surface's U and V directions.
+fn world_units_per_uv(dp_duv: mat2x3<f32>) -> vec2<f32> {
+  if (all(dp_duv[0] == vec3<f32>(0.0)) && all(dp_duv[1] == vec3<f32>(0.0))) {
+    return vec2<f32>(1.0);
+  }
+  return vec2<f32>(length(dp_duv[0]), length(dp_duv[1]));
+}
+
+// Room box in room cell space, its x/y faces are scaled by `back_scale` at `max.z`.
+struct RoomBox {
+  min: vec3<f32>,
+  max: vec3<f32>,
+  back_scale: vec2<f32>,
+};
+
+// x/y scale of the room's side faces at depth `z`.
+fn room_scale_at(room: RoomBox, z: f32) -> vec2<f32> {
+  return mix(vec2<f32>(1.0), room.back_scale, (z - room.min.z) / (room.max.z - room.min.z));
+}
+
+// Distance along `d` from `p` to the nearest of a min and max face moving `slope_min`/`slope_max`
+// per unit of z. Only faces the ray is approaching count.
+fn slanted_faces_distance(p: f32, d: f32, dz: f32, face_min: f32, face_max: f32, slope_min: f32, slope_max: f32) -> f32 {
+  let v_min = d - slope_min * dz;
+  let v_max = d - slope_max * dz;
+  var k = 1e30;
+  if (v_max > 0.0) {
+    k = min(k, (face_max - p) / v_max);
+  }
+  if (v_min < 0.0) {
+    k = min(k, (face_min - p) / v_min);
+  }
+  return k;
+}
+
+// Per-axis distances along `dir` from `pos` to the room faces it's heading towards.
+fn room_face_distances(room: RoomBox, pos: vec3<f32>, dir: vec3<f32>) -> vec3<f32> {
+  let scale = room_scale_at(room, pos.z);
+  let slope = (room.back_scale - 1.0) / (room.max.z - room.min.z);
+  let k_x = slanted_faces_distance(pos.x, dir.x, dir.z,
+    room.min.x * scale.x, room.max.x * scale.x, room.min.x * slope.x, room.max.x * slope.x);
+  let k_y = slanted_faces_distance(pos.y, dir.y, dir.z,
+    room.min.y * scale.y, room.max.y * scale.y, room.min.y * slope.y, room.max.y * slope.y);
+  let k_z = (select(room.min.z, room.max.z, dir.z > 0.0) - pos.z) / dir.z;
+  return vec3<f32>(k_x, k_y, k_z);
+}
+
+// Map a point in the room to the unit room box the atlas was rendered for.
+fn room_to_unit(room: RoomBox, pos: vec3<f32>) -> vec3<f32> {
+  let scale = room_scale_at(room, pos.z);
+  let face_min = vec3<f32>(room.min.xy * scale, room.min.z);
+  let face_max = vec3<f32>(room.max.xy * scale, room.max.z);
+  return (pos - face_min) / (face_max - face_min) * 2.0 - 1.0;
+}
+
+// Room UV of a hit in the unit room box.
+fn room_uv_from_hit(hit: vec3<f32>, far_frac: f32, depth_scale: f32) -> vec2<f32> {
+  // 0.0 - 1.0 room depth
+  var interp = hit.z * 0.5 + 0.5;
+
+  // account for perspective in "room" textures
+  // assumes camera with an fov of 53.13 degrees (atan(0.5))
+  let real_z = clamp(interp, 0.0, 1.0) / depth_scale + 1.0;
+  interp = 1.0 - (1.0 / real_z);
+  interp *= depth_scale + 1.0;
+
+  // iterpolate from wall back to near wall
+  return hit.xy * mix(1.0, far_frac, interp) * 0.5 + 0.5;
+}
+
+// Base color factor that `pbr_input_from_standard_material` applies to the texture sample.
+fn base_color_scale(in: VertexOutput) -> vec3<f32> {
+  var color_scale = pbr_bindings::material.base_color.rgb;
+#ifdef VERTEX_COLORS
+  color_scale *= in.color.rgb;
+#endif
+  return color_scale;
+}
+
+// Inverse of the sRGB decode of sRGB textures.
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+  let low = color * 12.92;
+  let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+  return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+// sRGB decode.
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+  let low = color / 12.92;
+  let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+  return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+// The rooms are in `room_images` or the base color atlas.
+fn has_room_atlas() -> bool {
+#ifdef FAKE_INTERIOR_ROOM_IMAGES
+  return true;
+#else
+  return (pbr_bindings::material.flags & pbr_types::STANDARD_MATERIAL_FLAGS_BASE_COLOR_TEXTURE_BIT) != 0u;
+#endif
+}
+
+// Base color atlas sample, with the sRGB decode undone for linear atlases. Samples the
+// room's layer of `room_images` instead when it's set.
+fn sample_base_color(uv: vec2<f32>) -> vec4<f32> {
+#ifdef FAKE_INTERIOR_ROOM_IMAGES
+  let color = textureSample(room_images, room_images_sampler, uv, room_layer);
+#else
+  let color = textureSample(base_color_texture, base_color_sampler, uv);
+#endif
+  if (material.base_color_is_linear != 0u) {
+    return vec4<f32>(linear_to_srgb(color.rgb), color.a);
+  }
+  return color;
+}
+
+// The lit rooms are in `emissive_layers` or the emissive atlas.
+fn has_emissive_atlas() -> bool {
+#ifdef FAKE_INTERIOR_EMISSIVE_LAYERS
+  return true;
+#else
+  return (pbr_bindings::material.flags & pbr_types::STANDARD_MATERIAL_FLAGS_EMISSIVE_TEXTURE_BIT) != 0u;
+#endif
+}
+
+// Emissive atlas sample, nudged by `emissive_uv_offset` to line up with the base color atlas.
+// Cross-fades the two `emissive_layers` around `emissive_layer` when they are set.
+fn sample_emissive(uv: vec2<f32>) -> vec3<f32> {
+  let emissive_uv = uv + material.emissive_uv_offset;
+#ifdef FAKE_INTERIOR_EMISSIVE_LAYERS
+  let last = f32(textureNumLayers(emissive_layers) - 1u);
+  let layer = clamp(material.emissive_layer, 0.0, last);
+  let first = floor(layer);
+  let a = textureSample(emissive_layers, emissive_layers_sampler, emissive_uv, u32(first)).rgb;
+  let b = textureSample(emissive_layers, emissive_layers_sampler, emissive_uv, u32(min(first + 1.0, last))).rgb;
+  return mix(a, b, layer - first);
+#else
+  return textureSample(pbr_bindings::emissive_texture, pbr_bindings::emissive_sampler, emissive_uv).rgb;
+#endif
+}
+
+#ifndef PREPASS_PIPELINE
+// Night mode: only the glow of lit rooms on an otherwise dark wall.
+fn night_output(in: VertexOutput, light: vec3<f32>, ceiling_glow: vec3<f32>, window: f32, reveal: f32) -> FragmentOutput {
+  var pbr_input = pbr_types::pbr_input_new();
+  pbr_input.frag_coord = in.position;
+  pbr_input.world_position = in.world_position;
+  pbr_input.material.flags = pbr_bindings::material.flags;
+
+  var emissive = pbr_bindings::material.emissive.rgb * light;
+  if (material.emissive_from_base_alpha != 0u) {
+    emissive *= sample_base_color(in.uv).a;
+  } else if (has_emissive_atlas()) {
+    emissive *= sample_emissive(in.uv);
+  }
+  // ambient floor so unlit rooms aren't pitch black.
+  if (material.interior_ambient.a > 0.0) {
+    let interior = sample_base_color(in.uv).rgb;
+    emissive = max(emissive, material.interior_ambient.rgb * material.interior_ambient.a * interior);
+  }
+  emissive += ceiling_glow;
+  // solid wall cells and mullions stay dark.
+  emissive *= window;
+  emissive = mix(emissive, material.reveal_color.rgb, reveal);
+
+  var out: FragmentOutput;
+  out.color = main_pass_post_lighting_processing(pbr_input, vec4<f32>(emissive, 1.0));
+  return out;
+}
+
+// Atlas debug: the whole atlas flat on the surface, unlit, with the room cells outlined.
+fn atlas_debug_output(in: VertexOutput) -> FragmentOutput {
+  var pbr_input = pbr_types::pbr_input_new();
+  pbr_input.frag_coord = in.position;
+  pbr_input.world_position = in.world_position;
+  pbr_input.material.flags = pbr_bindings::material.flags;
+
+  let uv = fract(in.uv / material.uv_tiling);
+  var color = sample_base_color(uv).rgb;
+  let cell_uv = uv * material.atlas_rooms;
+  let cell = fract(cell_uv);
+  let line_width = fwidth(cell_uv) * 1.5;
+  if (any(cell < line_width) || any(cell > 1.0 - line_width)) {
+    color = vec3<f32>(1.0, 0.0, 1.0);
+  }
+
+  var out: FragmentOutput;
+  out.color = main_pass_post_lighting_processing(pbr_input, vec4<f32>(color, 1.0));
+  return out;
+}
+#endif
+
 @fragment
 fn fragment(
 #ifdef MESHLET_MESH_MATERIAL_PASS
@@ -60,67 +467,347 @@ fn fragment(
 #endif
 
   var in = v_in;
+  // a room image is a single room atlas.
+#ifdef FAKE_INTERIOR_ROOM_IMAGES
+  let atlas_rooms = vec2<f32>(1.0);
+#else
   let atlas_rooms = material.atlas_rooms;
-  let UV = in.uv * material.rooms;
+#endif
+
+#ifndef PREPASS_PIPELINE
+  if (material.atlas_debug != 0u) {
+    return atlas_debug_output(in);
+  }
+#endif
+
+  // per-instance overrides of the shared material parameters.
+  var depth = material.depth;
+  var room_seed_scale = material.room_seed;
+  var tint = vec4<f32>(1.0);
+  var power = 1.0;
+#ifdef FAKE_INTERIOR_INSTANCE_PARAMS
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+  let instance = find_instance(in.instance_index);
+  if ((instance.flags & INSTANCE_FLAG_DEPTH) != 0u) {
+    depth = instance.depth;
+  }
+  if ((instance.flags & INSTANCE_FLAG_ROOM_SEED) != 0u) {
+    room_seed_scale = instance.room_seed;
+  }
+  tint = instance.tint;
+  if ((instance.flags & INSTANCE_FLAG_UNPOWERED) != 0u) {
+    power = 0.0;
+  }
+#endif
+#endif
+  // glow of the lit rooms, vertex colors tint it as well, the base color already has them.
+  var light_tint = tint.rgb * material.emission_intensity * power;
+#ifdef VERTEX_COLORS
+  light_tint *= in.color.rgb;
+#endif
+  // jitter the room grid per entity, always keeping whole rooms.
+  var rooms = material.rooms;
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+  if (any(material.rooms_jitter != vec2<f32>(0.0))) {
+    let position = instance_position(in.instance_index);
+    let jitter_seed = random1D(dot(position, vec3<f32>(1.0, 57.0, 113.0))) + material.room_seed;
+    let jitter = random2D(jitter_seed) * 2.0 - 1.0;
+    rooms = max(vec2<f32>(1.0), round(rooms * (1.0 + material.rooms_jitter * jitter)));
+  }
+#endif
+  // one room per UV island.
+  if (material.uv_island_rooms != 0u) {
+    rooms = vec2<f32>(1.0);
+  }
+
+  // normalize the surface UVs so `rooms` counts cover the whole surface
+  let surface_uv = in.uv / material.uv_tiling;
+  let UV = surface_uv * rooms;
+  // world-space size of one room cell
+  // derivatives in uniform control flow.
+  let dp_duv = world_per_uv(in.world_position.xyz, surface_uv);
+  let cell_size = world_units_per_uv(dp_duv) / rooms;
+  let cell_width = 0.5 * (cell_size.x + cell_size.y);
   // room uvs
   let room_uv = fract(UV);
   var room_index_uv = floor(UV);
-  let room_index = (room_index_uv.x + room_index_uv.y * material.rooms.x);
-  let room_seed = room_index * material.room_seed;
+  let room_index = (room_index_uv.x + room_index_uv.y * rooms.x);
+  var room_seed = room_index * room_seed_scale;
+  var emission_seed = material.emission_seed;
+  var flicker_seed = room_index * material.flicker_seed;
+  // rooms of their own per entity, seeded by its world position.
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+  if (material.world_seed_scale != 0.0) {
+    let position = instance_position(in.instance_index);
+    let world_seed = random1D(dot(position, vec3<f32>(1.0, 57.0, 113.0)) + 5.0) * material.world_seed_scale;
+    room_seed += world_seed;
+    emission_seed += world_seed;
+    flicker_seed += world_seed;
+  }
+#endif
+  // rooms of their own per UV island, seeded by the world position of the island's UV origin.
+  // It's the same over a flat island, snap it to a quarter of the island to hide the rounding
+  // errors.
+  if (material.uv_island_rooms != 0u) {
+    let origin = in.world_position.xyz - dp_duv * surface_uv;
+    let snap = max(length(dp_duv[0]), 1e-4) * 0.25;
+    let island = bitcast<vec3<u32>>(vec3<i32>(round(origin / snap)));
+    let island_hash = pcg_hash(island.x ^ pcg_hash(island.y ^ pcg_hash(island.z)));
+    let island_seed = f32(island_hash >> 8u) / 16777216.0 * 1000.0;
+    room_seed += island_seed;
+    emission_seed += island_seed;
+    flicker_seed += island_seed;
+  }
+#ifdef FAKE_INTERIOR_ROOM_IMAGES
+  let layers = textureNumLayers(room_images);
+  room_layer = min(u32(random1D(room_seed + 89.0) * f32(layers)), layers - 1u);
+#endif
+  // mirror every other room cell along each axis so neighbours share their walls.
+  var cell_mirror = vec2<f32>(1.0);
+  if (material.mirror_tiling != 0u) {
+    cell_mirror = select(vec2<f32>(1.0), vec2<f32>(-1.0), fract(room_index_uv * 0.5) >= vec2<f32>(0.5));
+  }
 
-  // randomize the rooms
-  let n = floor(random2D(room_seed) * atlas_rooms);
+  // randomize the rooms, unless the index map picks the room of this cell.
+  var n = floor(random2D(room_seed) * atlas_rooms);
+#ifdef FAKE_INTERIOR_ROOM_INDEX_MAP
+  let map_size = vec2<f32>(textureDimensions(room_index_map));
+  let map_cell = room_index_uv - floor(room_index_uv / map_size) * map_size;
+  let picked = textureLoad(room_index_map, vec2<u32>(map_cell), 0);
+  if (picked.a >= 0.5) {
+    let k = round(picked.r * 255.0);
+    n = vec2<f32>(k % atlas_rooms.x, floor(k / atlas_rooms.x)) - room_index_uv;
+#ifdef FAKE_INTERIOR_ROOM_IMAGES
+    room_layer = min(u32(k), layers - 1u);
+#endif
+  }
+#endif
   room_index_uv += n;
 
   // get room depth from room atlas alpha else use the Depth paramater
   let depth_uv = fract((room_index_uv + 0.5) / atlas_rooms);
-  var far_frac = textureSample(base_color_texture, base_color_sampler, depth_uv).a;
+  // the room depth isn't read from the atlas alpha in night mode or when
+  // the alpha holds the lit mask.
+  var far_frac = 1.0;
+  if (material.night_only == 0u && material.emissive_from_base_alpha == 0u) {
+    far_frac = sample_base_color(depth_uv).a;
+  }
   if (far_frac >= 0.99) {
-    far_frac = material.depth;
+    if (material.use_depth_world != 0u) {
+      // convert the world-space room depth into the back wall's fraction of the room cell.
+      let cell_scale = cell_width / max(material.depth_world, 0.0001);
+      far_frac = cell_scale / (1.0 + cell_scale);
+    } else {
+      far_frac = depth;
+      // hashed per-room depth, stable across frames.
+      if (material.depth_variation > 0.0) {
+        let variation = (random1D(room_seed + 61.0) * 2.0 - 1.0) * material.depth_variation;
+        far_frac = clamp(depth + variation, 0.0, 0.99);
+      }
+    }
     if (far_frac >= 1.0 || far_frac < 0.0) {
       far_frac = 0.5;
     }
   }
 
-  let depth_scale = 1.0 / (1.0 - far_frac) - 1.0;
+  // `depth = 0.0` (or a zero depth in the atlas alpha) is a flat decal of the room at the
+  // surface UV. Its ray goes straight to the back wall, keeping the rest of the shading finite.
+  let flat_room = far_frac <= 0.0;
+  let depth_scale = select(1.0 / (1.0 - far_frac) - 1.0, 1.0, flat_room);
 
   // raytrace material from view dir
-  var pos = vec3<f32>(room_uv * 2.0 - 1.0, -1.0);
-  let view_dir = in.world_position.xyz - view.world_position;
-  let bitangent = normalize(cross(in.world_tangent.xyz, in.world_normal));
+  // stretch the room box by its aspect, the smaller side keeps spanning the window.
+  let room_aspect = material.room_aspect / min(material.room_aspect.x, material.room_aspect.y);
+  var box_min = material.box_min * vec3<f32>(room_aspect, 1.0);
+  var box_max = material.box_max * vec3<f32>(room_aspect, 1.0);
+  // the rooms on the surface edges end at the edge instead of leaking past a corner.
+  if (material.clip_at_edges != 0u) {
+    let cell = floor(UV);
+    let at_min = cell <= vec2<f32>(0.0);
+    let at_max = cell >= ceil(rooms) - 1.0;
+    // sides of the room box in room cell space, swapped where the cell is mirrored.
+    let mirrored = cell_mirror < vec2<f32>(0.0);
+    var clip_min = select(at_min, at_max, mirrored);
+    var clip_max = select(at_max, at_min, mirrored);
+    if (material.double_sided_interior != 0u && !is_front) {
+      let clip_x = clip_min.x;
+      clip_min.x = clip_max.x;
+      clip_max.x = clip_x;
+    }
+    box_min = vec3<f32>(select(box_min.xy, max(box_min.xy, vec2<f32>(-1.0)), clip_min), box_min.z);
+    box_max = vec3<f32>(select(box_max.xy, min(box_max.xy, vec2<f32>(1.0)), clip_max), box_max.z);
+  }
+  var pos = vec3<f32>((room_uv * 2.0 - 1.0) * cell_mirror, box_min.z);
+  // orthographic cameras have a constant view direction (the camera forward)
+  let is_orthographic = view.clip_from_view[3].w == 1.0;
+  var view_dir = in.world_position.xyz - view.world_position;
+  if (is_orthographic) {
+    view_dir = -view.world_from_view[2].xyz;
+  }
+  // Bevy's mesh vertex shader outputs the normal and tangent after skinning and morph
+  // targets. Interpolated over subdivided, curved or skinned meshes they are neither unit
+  // length nor perpendicular, rebuild an orthonormal frame so the rooms don't wobble.
+  var world_normal = normalize(in.world_normal);
+  // imported meshes with a zeroed tangent attribute: the surface's U direction from the UV
+  // derivatives instead, or the tangent frame collapses and the interior is black.
+  var mesh_tangent = in.world_tangent.xyz;
+  if (dot(mesh_tangent, mesh_tangent) < 1e-12) {
+    mesh_tangent = dp_duv[0];
+  }
+  var world_tangent = normalize(mesh_tangent - world_normal * dot(world_normal, mesh_tangent));
+  // back faces of double sided interiors look into a room behind them. Rotate the
+  // tangent frame around the bitangent so the room isn't mirrored.
+  if (material.double_sided_interior != 0u && !is_front) {
+    world_normal = -world_normal;
+    world_tangent = -world_tangent;
+    pos.x = -pos.x;
+  }
+  // mirrored UVs or entities (negative scale): Bevy folds both into the tangent's `w`, flip
+  // the bitangent with it so the rooms aren't inside out. Without a tangent attribute the
+  // V direction of the UV derivatives gives the side instead.
+  var bitangent_sign = in.world_tangent.w;
+  if (bitangent_sign == 0.0) {
+    bitangent_sign = dot(cross(world_tangent, world_normal), dp_duv[1]);
+  }
+  var bitangent = cross(world_tangent, world_normal);
+  if (bitangent_sign < 0.0) {
+    bitangent = -bitangent;
+  }
   // get tangent space camera vector
-  let tangent_view_dir = vec3(
-    dot(view_dir, in.world_tangent.xyz),
+  // scale by the cell's aspect so non-square cells keep a proportional room box
+  let cell_aspect = cell_width / max(cell_size, vec2<f32>(0.0001));
+  let surface_view_dir = rotate_interior(vec3<f32>(
+    dot(view_dir, world_tangent),
     dot(view_dir, bitangent),
-    dot(view_dir, in.world_normal) * -depth_scale);
-  let id = 1.0 / tangent_view_dir;
-  let k = abs(id) - pos * id;
+    dot(view_dir, world_normal)));
+  var tangent_view_dir = surface_view_dir * vec3<f32>(
+    cell_aspect.x * cell_mirror.x,
+    cell_aspect.y * cell_mirror.y,
+    -depth_scale);
+  tangent_view_dir.z = max(tangent_view_dir.z, max(length(tangent_view_dir.xy) * MIN_VIEW_SLOPE, 1e-8));
+  if (flat_room) {
+    tangent_view_dir = vec3<f32>(0.0, 0.0, 1.0);
+  }
+  let room = RoomBox(box_min, box_max, material.back_wall_scale);
+  // the whole room image flat in the window, without parallax.
+  let flat_uv = pos.xy * 0.5 + 0.5;
+  // `near_clip`: start the ray on a plane inside the room box, in the box at grazing angles.
+  if (material.near_clip > 0.0 && !flat_room) {
+    let clip_z = mix(room.min.z, room.max.z, min(material.near_clip, MAX_NEAR_CLIP));
+    pos += (clip_z - pos.z) / tangent_view_dir.z * tangent_view_dir;
+    let clip_scale = room_scale_at(room, clip_z);
+    pos = vec3<f32>(clamp(pos.xy, room.min.xy * clip_scale, room.max.xy * clip_scale), clip_z);
+  }
+  let k = room_face_distances(room, pos, tangent_view_dir);
   let k_min = min(min(k.x, k.y), k.z);
   pos += k_min * tangent_view_dir;
+  // map the hit back into the unit room box the atlas was rendered for
+  let hit = room_to_unit(room, pos);
+#ifdef FAKE_INTERIOR_FLAT
+  let interior_uv = flat_uv;
+#else
+  let interior_uv = select(room_uv_from_hit(hit, far_frac, depth_scale), flat_uv, flat_room);
+#endif
 
-  // 0.0 - 1.0 room depth
-  var interp = pos.z * 0.5 + 0.5;
+  // shade the faces of the room box, +y is the floor in the atlas.
+  var face_shade = 1.0;
+#ifndef FAKE_INTERIOR_NO_DETAILS
+  if (material.shade_box_faces != 0u) {
+    if (k.x == k_min) {
+      face_shade = SIDE_WALL_SHADE;
+    } else if (k.y == k_min) {
+      face_shade = select(CEILING_SHADE, FLOOR_SHADE, tangent_view_dir.y > 0.0);
+    }
+  }
+#endif
+  // darken towards the room corners.
+  let vignette_dist = interior_uv * 2.0 - 1.0;
+  face_shade *= clamp(1.0 - material.room_vignette * dot(vignette_dist, vignette_dist) * 0.5, 0.0, 1.0);
 
-  // account for perspective in "room" textures
-  // assumes camera with an fov of 53.13 degrees (atan(0.5))
-  let real_z = clamp(interp, 0.0, 1.0) / depth_scale + 1.0;
-  interp = 1.0 - (1.0 / real_z);
-  interp *= depth_scale + 1.0;
+#ifndef PREPASS_PIPELINE
+  // sunlight through the window, reaching from the window to a depth that grows the more
+  // directly the sun shines at the wall. The prepasses don't have the lights.
+  if (material.sun_penetration > 0.0 && lights.n_directional_lights > 0u) {
+    let facing = max(dot(world_normal, lights.directional_lights[0].direction_to_light), 0.0);
+    let sunlit = clamp((facing - (hit.z * 0.5 + 0.5)) * 10.0 + 0.5, 0.0, 1.0);
+    face_shade *= 1.0 + material.sun_penetration * facing * sunlit;
+  }
+#endif
 
-  // iterpolate from wall back to near wall
-  var interior_uv = pos.xy * mix(1.0, far_frac, interp);
-  interior_uv = interior_uv * 0.5 + 0.5;
+  // glossy floor: follow the ray mirrored by the floor (+y in the atlas) to the rest of the room.
+  var floor_uv = interior_uv;
+  var floor_gloss = 0.0;
+#ifndef FAKE_INTERIOR_NO_DETAILS
+  if (material.floor_gloss > 0.0 && k.y == k_min && tangent_view_dir.y > 0.0) {
+    let mirrored_dir = tangent_view_dir * vec3<f32>(1.0, -1.0, 1.0);
+    let k_floor = room_face_distances(room, pos, mirrored_dir);
+    let floor_hit = pos + min(min(k_floor.x, k_floor.y), k_floor.z) * mirrored_dir;
+    floor_uv = room_uv_from_hit(room_to_unit(room, floor_hit), far_frac, depth_scale);
+    floor_gloss = material.floor_gloss;
+  }
+#endif
+
+  // some rooms have a window in the back wall.
+  var far_window = 0.0;
+#ifndef FAKE_INTERIOR_NO_EMISSIVE
+  if (k.z == k_min && random1D(room_seed + 17.0) < material.far_window) {
+    let back_uv = hit.xy * 0.5 + 0.5;
+    let window_min = material.far_window_rect.xy;
+    let in_window = step(window_min, back_uv) * step(back_uv, window_min + material.far_window_rect.zw);
+    far_window = in_window.x * in_window.y;
+  }
+#endif
+
+  // some rooms have an occupant standing in front of the back wall.
+  var occupied = 0.0;
+#ifdef FAKE_INTERIOR_OCCUPANTS
+  if (k.z == k_min && random1D(room_seed + 31.0) < material.occupant_chance) {
+    occupied = 1.0;
+  }
+#endif
 
   // Update UV for PBR shader.
   in.uv = fract((room_index_uv + interior_uv) / atlas_rooms);
 
+  // reveal line along the room cell edges.
+  let edge_dist = min(room_uv, 1.0 - room_uv);
+  let reveal = material.reveal_color.a * (1.0 - step(material.reveal_width, min(edge_dist.x, edge_dist.y)));
+
+  // some cells are solid wall instead of a window.
+  var window = select(1.0, 0.0, random1D(room_seed + 73.0) < material.solid_threshold);
+  // the mullions are wall too, the derivatives of `UV` avoid mip seams at the cell edges.
+#ifdef FAKE_INTERIOR_MULLION_MASK
+  let mullion = textureSampleGrad(mullion_mask_texture, mullion_mask_sampler, room_uv, dpdx(UV), dpdy(UV)).a;
+  window *= 1.0 - mullion;
+#endif
+  // fade into the wall towards the cell edges, hiding the seam with the base color.
+  if (material.border_blend > 0.0) {
+    window *= smoothstep(0.0, material.border_blend, min(edge_dist.x, edge_dist.y));
+  }
+
   // Randomly turn on room light.
-  var has_light = random1D(room_index * material.light_seed);
-  if has_light >= material.light_threshold {
-    has_light = 0.0;
-  } else {
-    has_light = 1.0;
+#ifdef FAKE_INTERIOR_NO_EMISSIVE
+  let has_light = 0.0;
+#else
+  let has_light = select(0.0, 1.0, room_random(room_index, emission_seed) < material.emission_threshold);
+  // glow color of the room from the light palette.
+#ifdef FAKE_INTERIOR_LIGHT_PALETTE
+  let palette_size = textureDimensions(light_palette_texture).x;
+  let entry = min(u32(room_random(room_index, emission_seed + 0.5) * f32(palette_size)), palette_size - 1u);
+  light_tint *= textureLoad(light_palette_texture, vec2<u32>(entry, 0u), 0).rgb;
+#endif
+  // TV rooms: a new bluish white shade and brightness every few frames of the show.
+  if (random1D(flicker_seed + 91.0) < material.tv_flicker) {
+    let shot = floor(interior_time() * TV_CUTS_PER_SECOND + random1D(flicker_seed + 17.0) * 8.0);
+    let shade = random1D(flicker_seed + shot * 0.37);
+    let brightness = random1D(flicker_seed + shot * 0.61 + 5.0);
+    light_tint *= mix(vec3<f32>(0.45, 0.6, 1.0), vec3<f32>(0.85, 0.9, 1.0), shade) * mix(0.35, 1.0, brightness);
   }
+#endif
+  // light fixture in the middle of the ceiling (-y in the atlas), a point in the room box
+  // so it stays put as the view moves.
+  let fixture_dist = hit - vec3<f32>(0.0, -1.0, 0.0);
+  let ceiling_glow = has_light * light_tint * material.ceiling_light / (1.0 + 16.0 * dot(fixture_dist, fixture_dist));
 
   // If we're in the crossfade section of a visibility range, conditionally
   // discard the fragment according to the visibility pattern.
@@ -128,16 +815,274 @@ fn fragment(
   pbr_functions::visibility_range_dither(in.position, in.visibility_range_dither);
 #endif
 
+#ifndef PREPASS_PIPELINE
+  if (material.night_only != 0u) {
+    return night_output(in, has_light * light_tint, ceiling_glow, window, reveal);
+  }
+#endif
+
   // generate a PbrInput struct from the StandardMaterial bindings
   var pbr_input = pbr_input_from_standard_material(in, is_front);
+  // normal mapping switched off: the vertex normal, like without a normal map.
+  if (material.use_normal_map == 0u) {
+    pbr_input.N = normalize(pbr_input.world_normal);
+  }
+#ifndef PREPASS_PIPELINE
+  // the sun's shadow on the window, e.g. of a tree, also falls into the room.
+  if (material.receive_exterior_shadows != 0u && lights.n_directional_lights > 0u &&
+      (pbr_input.flags & MESH_FLAGS_SHADOW_RECEIVER_BIT) != 0u &&
+      (lights.directional_lights[0].flags & DIRECTIONAL_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
+    let view_z = dot(vec4<f32>(
+      view.view_from_world[0].z,
+      view.view_from_world[1].z,
+      view.view_from_world[2].z,
+      view.view_from_world[3].z), in.world_position);
+    let visibility = shadows::fetch_directional_shadow(0u, in.world_position, pbr_input.world_normal, view_z);
+    face_shade *= mix(EXTERIOR_SHADOW_SHADE, 1.0, visibility);
+  }
+#endif
+#ifdef FAKE_INTERIOR_NO_EMISSIVE
+  // nothing glows, drop the emissive sample.
+  pbr_input.material.emissive = vec4<f32>(vec3<f32>(0.0), pbr_input.material.emissive.a);
+#else
+  // misaligned emissive atlas or emissive layers: `pbr_input_from_standard_material` sampled
+  // the emissive atlas at the base color UV.
+#ifdef FAKE_INTERIOR_EMISSIVE_LAYERS
+  let resample_emissive = true;
+#else
+  let resample_emissive = any(material.emissive_uv_offset != vec2<f32>(0.0));
+#endif
+  if (resample_emissive && has_emissive_atlas()) {
+    pbr_input.material.emissive = vec4<f32>(
+      pbr_bindings::material.emissive.rgb * sample_emissive(in.uv),
+      pbr_input.material.emissive.a);
+  }
+#endif
 
-  pbr_input.material.emissive *= has_light;
+  // linear atlas: `pbr_input_from_standard_material` decoded its sample as sRGB.
+  if (material.base_color_is_linear != 0u &&
+      (pbr_bindings::material.flags & pbr_types::STANDARD_MATERIAL_FLAGS_BASE_COLOR_TEXTURE_BIT) != 0u) {
+    pbr_input.material.base_color = vec4<f32>(
+      base_color_scale(in) * sample_base_color(in.uv).rgb,
+      pbr_input.material.base_color.a);
+  }
+  // room images: `pbr_input_from_standard_material` only knows the base color atlas.
+#ifdef FAKE_INTERIOR_ROOM_IMAGES
+  let room_color = sample_base_color(in.uv);
+  pbr_input.material.base_color = vec4<f32>(
+    base_color_scale(in) * room_color.rgb,
+    pbr_bindings::material.base_color.a * room_color.a);
+#endif
+
+#ifndef FAKE_INTERIOR_NO_DETAILS
+  // frosted glass: average the room over a disc of `frost_amount` room cells.
+  let frost_taps = min(material.frost_taps, FROST_MAX_TAPS);
+  if (material.frost_amount > 0.0 && frost_taps > 0u && has_room_atlas()) {
+#ifdef FAKE_INTERIOR_NO_EMISSIVE
+    let has_emissive_texture = false;
+#else
+    let has_emissive_texture = has_emissive_atlas();
+#endif
+    var color = vec4<f32>(0.0);
+    var emissive = vec3<f32>(0.0);
+    for (var i = 0u; i < frost_taps; i += 1u) {
+      // Vogel disc, evenly spread for any tap count.
+      let radius = sqrt((f32(i) + 0.5) / f32(frost_taps)) * material.frost_amount;
+      let angle = f32(i) * GOLDEN_ANGLE;
+      let offset = vec2<f32>(cos(angle), sin(angle)) * radius;
+      // stay inside this room of the atlas.
+      let tap_uv = fract((room_index_uv + clamp(interior_uv + offset, vec2<f32>(0.0), vec2<f32>(1.0))) / atlas_rooms);
+      color += sample_base_color(tap_uv);
+      if (has_emissive_texture) {
+        emissive += sample_emissive(tap_uv);
+      }
+    }
+    color /= f32(frost_taps);
+    pbr_input.material.base_color = vec4<f32>(
+      base_color_scale(in) * color.rgb,
+      pbr_bindings::material.base_color.a * color.a);
+    if (has_emissive_texture) {
+      pbr_input.material.emissive = vec4<f32>(
+        pbr_bindings::material.emissive.rgb * emissive / f32(frost_taps),
+        pbr_input.material.emissive.a);
+    }
+  }
+
+  // chromatic aberration: shift the red and blue interior samples along the view direction.
+  if (material.chromatic_aberration != 0.0 && has_room_atlas()) {
+    let color_scale = base_color_scale(in);
+    let shift = tangent_view_dir.xy / length(tangent_view_dir) * material.chromatic_aberration;
+    // stay inside this room of the atlas.
+    let uv_r = (room_index_uv + clamp(interior_uv + shift, vec2<f32>(0.0), vec2<f32>(1.0))) / atlas_rooms;
+    let uv_b = (room_index_uv + clamp(interior_uv - shift, vec2<f32>(0.0), vec2<f32>(1.0))) / atlas_rooms;
+    pbr_input.material.base_color.r = color_scale.r * sample_base_color(fract(uv_r)).r;
+    pbr_input.material.base_color.b = color_scale.b * sample_base_color(fract(uv_b)).b;
+  }
+
+  // glossy floor reflection.
+  if (material.floor_gloss > 0.0 && has_room_atlas()) {
+    let floor_atlas_uv = fract((room_index_uv + floor_uv) / atlas_rooms);
+    let reflection = base_color_scale(in) * sample_base_color(floor_atlas_uv).rgb;
+    pbr_input.material.base_color = vec4<f32>(
+      mix(pbr_input.material.base_color.rgb, reflection, floor_gloss),
+      pbr_input.material.base_color.a);
+  }
+#endif
+
+  // lit mask from the base color alpha.
+  if (material.emissive_from_base_alpha != 0u) {
+    pbr_input.material.emissive = vec4<f32>(
+      pbr_bindings::material.emissive.rgb * pbr_input.material.base_color.a,
+      pbr_input.material.emissive.a);
+    pbr_input.material.base_color.a = pbr_bindings::material.base_color.a;
+  }
+  pbr_input.material.emissive *= vec4<f32>(has_light * light_tint, 1.0);
+  pbr_input.material.base_color *= tint;
+  // the wall's occlusion at the surface UV, `in.uv` is in the atlas by now.
+  if (material.ao_affects_interior > 0.0 &&
+      (pbr_bindings::material.flags & pbr_types::STANDARD_MATERIAL_FLAGS_OCCLUSION_TEXTURE_BIT) != 0u) {
+    let surface_ao_uv = (pbr_bindings::material.uv_transform * vec3<f32>(v_in.uv, 1.0)).xy;
+    let ao = textureSample(pbr_bindings::occlusion_texture, pbr_bindings::occlusion_sampler, surface_ao_uv).r;
+    let interior_ao = mix(1.0, ao, material.ao_affects_interior);
+    pbr_input.material.base_color = vec4<f32>(
+      pbr_input.material.base_color.rgb * interior_ao,
+      pbr_input.material.base_color.a);
+    pbr_input.material.emissive = vec4<f32>(
+      pbr_input.material.emissive.rgb * interior_ao,
+      pbr_input.material.emissive.a);
+  }
+  pbr_input.material.base_color = vec4<f32>(
+    pbr_input.material.base_color.rgb * face_shade,
+    pbr_input.material.base_color.a);
+  var far_window_glow = material.far_window_color.rgb * material.far_window_color.a * far_window;
+  // see-through rooms: the sky through a hole in the back wall.
+  if (material.far_window_sky != 0u) {
+    let sky = mix(
+      material.sky_bottom_color.rgb * material.sky_bottom_color.a,
+      material.sky_top_color.rgb * material.sky_top_color.a,
+      normalize(view_dir).y * 0.5 + 0.5);
+    far_window_glow = sky * far_window;
+    pbr_input.material.base_color = vec4<f32>(
+      pbr_input.material.base_color.rgb * (1.0 - far_window),
+      pbr_input.material.base_color.a);
+    pbr_input.material.emissive = vec4<f32>(
+      pbr_input.material.emissive.rgb * (1.0 - far_window),
+      pbr_input.material.emissive.a);
+  }
+  pbr_input.material.emissive = vec4<f32>(
+    pbr_input.material.emissive.rgb + far_window_glow + ceiling_glow,
+    pbr_input.material.emissive.a);
+  // animated occupant silhouette, each room starts at a different frame.
+#ifdef FAKE_INTERIOR_OCCUPANTS
+  let frames = f32(max(material.occupant_frames, 1u));
+  let frame = floor(fract(interior_time() * material.occupant_fps / frames + random1D(flicker_seed + 47.0)) * frames);
+  let sprite_uv = vec2<f32>((frame + hit.x * 0.5 + 0.5) / frames, hit.y * 0.5 + 0.5);
+  let occupant = textureSampleLevel(occupant_texture, occupant_sampler, sprite_uv, 0.0) * occupied;
+  pbr_input.material.base_color = vec4<f32>(
+    mix(pbr_input.material.base_color.rgb, occupant.rgb, occupant.a),
+    pbr_input.material.base_color.a);
+  pbr_input.material.emissive = vec4<f32>(
+    pbr_input.material.emissive.rgb * (1.0 - occupant.a),
+    pbr_input.material.emissive.a);
+#endif
+  // retro palette: `color_levels` steps per channel, even in sRGB.
+  if (material.color_levels > 1u) {
+    let steps = f32(material.color_levels - 1u);
+    let srgb = linear_to_srgb(clamp(pbr_input.material.base_color.rgb, vec3<f32>(0.0), vec3<f32>(1.0)));
+    pbr_input.material.base_color = vec4<f32>(
+      srgb_to_linear(round(srgb * steps) / steps),
+      pbr_input.material.base_color.a);
+  }
+#ifndef FAKE_INTERIOR_NO_EMISSIVE
+  // ambient floor so unlit rooms aren't pitch black, lit rooms already glow brighter.
+  let ambient = material.interior_ambient.rgb * material.interior_ambient.a * pbr_input.material.base_color.rgb;
+  pbr_input.material.emissive = vec4<f32>(
+    max(pbr_input.material.emissive.rgb, ambient),
+    pbr_input.material.emissive.a);
+#endif
+  // solid wall cells and mullions: the plain base material, no interior.
+  if (window < 1.0) {
+    pbr_input.material.base_color = vec4<f32>(
+      mix(base_color_scale(in) * tint.rgb, pbr_input.material.base_color.rgb, window),
+      pbr_input.material.base_color.a);
+    pbr_input.material.emissive = vec4<f32>(
+      pbr_input.material.emissive.rgb * window,
+      pbr_input.material.emissive.a);
+  }
+#ifdef FAKE_INTERIOR_GLASS_ANISOTROPY
+  // brushed or streaked glass: Bevy's anisotropic lighting along `glass_aniso_direction`, not
+  // on the wall cells and mullions.
+  var aniso_direction = vec2<f32>(1.0, 0.0);
+  if (any(material.glass_aniso_direction != vec2<f32>(0.0))) {
+    aniso_direction = normalize(material.glass_aniso_direction);
+  }
+  pbr_input.anisotropy_strength = material.glass_anisotropy * window;
+  pbr_input.anisotropy_T = normalize(world_tangent * aniso_direction.x + bitangent * aniso_direction.y);
+  pbr_input.anisotropy_B = normalize(cross(pbr_input.world_normal, pbr_input.anisotropy_T));
+#endif
+#ifndef PREPASS_PIPELINE
+#ifndef ENVIRONMENT_MAP
+  // no environment map to reflect in the glass, reflect the sky gradient instead.
+  if (material.reflect_environment != 0u && window > 0.0) {
+    var reflect_normal = pbr_input.N;
+#ifdef FAKE_INTERIOR_GLASS_ANISOTROPY
+    // streak the sky: bend the normal across the streaks, like Bevy does for environment maps.
+    let bent_normal = normalize(cross(cross(pbr_input.anisotropy_B, pbr_input.V), pbr_input.anisotropy_B));
+    reflect_normal = normalize(mix(pbr_input.N, bent_normal, pbr_input.anisotropy_strength));
+#endif
+    let reflected = reflect(-pbr_input.V, reflect_normal);
+    let sky = mix(
+      material.sky_bottom_color.rgb * material.sky_bottom_color.a,
+      material.sky_top_color.rgb * material.sky_top_color.a,
+      reflected.y * 0.5 + 0.5);
+    let f0 = 0.16 * pbr_input.material.reflectance * pbr_input.material.reflectance;
+    let fresnel = f0 + (1.0 - f0) * pow(1.0 - max(dot(pbr_input.N, pbr_input.V), 0.0), 5.0);
+    pbr_input.material.emissive = vec4<f32>(
+      pbr_input.material.emissive.rgb + sky * fresnel * window,
+      pbr_input.material.emissive.a);
+  }
+#endif
+#ifdef FAKE_INTERIOR_PLANAR_REFLECTION
+  // planar reflection in the glass, rendered mirrored by the wall's plane. Not on the wall
+  // cells and mullions.
+  let screen_uv = (in.position.xy - view.viewport.xy) / view.viewport.zw;
+  let reflection = textureSample(planar_reflection_texture, planar_reflection_sampler, vec2<f32>(1.0 - screen_uv.x, screen_uv.y)).rgb;
+  let glass = material.glass_reflectance * window;
+  pbr_input.material.base_color = vec4<f32>(
+    pbr_input.material.base_color.rgb * (1.0 - glass),
+    pbr_input.material.base_color.a);
+  pbr_input.material.emissive = vec4<f32>(
+    mix(pbr_input.material.emissive.rgb, reflection, glass),
+    pbr_input.material.emissive.a);
+#endif
+#endif
+  pbr_input.material.base_color = vec4<f32>(
+    mix(pbr_input.material.base_color.rgb, material.reveal_color.rgb, reveal),
+    pbr_input.material.base_color.a);
+  pbr_input.material.emissive = vec4<f32>(
+    mix(pbr_input.material.emissive.rgb, material.reveal_color.rgb, reveal),
+    pbr_input.material.emissive.a);
   // alpha discard
   pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
 
 #ifdef PREPASS_PIPELINE
+#ifdef DEFERRED_PREPASS
   // write the gbuffer, lighting pass id, and optionally normal and motion_vector textures
   let out = deferred_output(in, pbr_input);
+#else
+  // normal prepass: write the (normal mapped) normal of the interior, same as the main pass.
+  var out: FragmentOutput;
+#ifdef NORMAL_PREPASS
+  out.normal = vec4<f32>(pbr_input.N * 0.5 + vec3<f32>(0.5), 1.0);
+#endif
+#ifdef MOTION_VECTOR_PREPASS
+  out.motion_vector = calculate_motion_vector(in.world_position, in.previous_world_position);
+#endif
+#ifdef DEPTH_CLAMP_ORTHO
+  // the interior stays at the wall's depth, only unclamped like Bevy's prepass (reversed-Z).
+  out.frag_depth = in.clip_position_unclamped.z;
+#endif
+#endif
 #else
   // in forward mode, we calculate the lit color immediately, and then apply some post-lighting effects here.
   // in deferred mode the lit color and these effects will be calculated in the deferred lighting shader
@@ -0,0 +1,135 @@
+//! Fake interior walls viewed through an orthographic (isometric) camera.
+
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+    render::{camera::ScalingMode, mesh::*},
+};
+
+use bevy_fake_interior::*;
+use std::f32::consts::FRAC_PI_2;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins((
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Isometric".into(),
+                    ..default()
+                }),
+                ..default()
+            })
+            .set(AssetPlugin {
+                mode: AssetMode::Processed,
+                ..default()
+            }),
+        LogDiagnosticsPlugin::default(),
+        FrameTimeDiagnosticsPlugin,
+    ));
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
+        .add_systems(Startup, setup)
+        .add_systems(Update, rotate_building);
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Building;
+
+/// set up a simple 3D scene
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    // ground
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(10.0, 10.0))),
+        MeshMaterial3d(materials.add(Color::WHITE)),
+        Transform::from_xyz(0.0, -1.0, 0.0),
+        Name::new("Ground"),
+    ));
+
+    let interior = interiors.add(StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
+            reflectance: 1.0,
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(4.0, 4.0),
+            depth: 0.5,
+            room_seed: 1.2,
+            emission_intensity: 10.0,
+            ..default()
+        },
+    });
+
+    // building made of four fake interior walls.
+    let wall = Mesh3d(
+        meshes.add(
+            PlaneMeshBuilder::from_length(2.0)
+                .subdivisions(0)
+                .build()
+                .with_generated_tangents()
+                .unwrap(),
+        ),
+    );
+    commands
+        .spawn((
+            Transform::default(),
+            Visibility::default(),
+            Building,
+            Name::new("Building"),
+        ))
+        .with_children(|building| {
+            for i in 0..4 {
+                let rotation = Quat::from_rotation_y(i as f32 * FRAC_PI_2);
+                building.spawn((
+                    wall.clone(),
+                    MeshMaterial3d(interior.clone()),
+                    Transform::from_translation(rotation * Vec3::new(0.0, 0.0, 1.0))
+                        .with_rotation(rotation * Quat::from_rotation_x(FRAC_PI_2)),
+                    Name::new(format!("Wall {i}")),
+                ));
+            }
+        });
+
+    // light
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 2000.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // isometric camera
+    commands.spawn((
+        Camera3d::default(),
+        Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: 6.0,
+            },
+            ..OrthographicProjection::default_3d()
+        }),
+        Transform::from_xyz(5.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        Name::new("Camera"),
+    ));
+}
+
+fn rotate_building(time: Res<Time>, mut query: Query<&mut Transform, With<Building>>) {
+    for mut transform in &mut query {
+        transform.rotate_y(time.delta_secs() * 0.2);
+    }
+}
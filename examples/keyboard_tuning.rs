@@ -0,0 +1,216 @@
+//! Tune the `FakeInteriorMaterial` with the keyboard, without an egui inspector.
+//!
+//! Shows how to change an extended material in place: `Assets::get_mut` marks the material
+//! as modified, so it's only called when a key changes something, otherwise the material
+//! would be uploaded to the GPU again every frame.
+
+use bevy::prelude::*;
+
+use bevy_fake_interior::*;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Keyboard tuning".into(),
+                    ..default()
+                }),
+                ..default()
+            })
+            .set(AssetPlugin {
+                mode: AssetMode::Processed,
+                ..default()
+            }),
+    );
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (tune, show_params).chain());
+
+    app.run();
+}
+
+/// The material that is tuned.
+#[derive(Resource)]
+struct Tuned(Handle<StandardFakeInteriorMaterial>);
+
+#[derive(Component)]
+struct ParamsText;
+
+/// A parameter changed by holding down its keys.
+struct Param {
+    name: &'static str,
+    increase: KeyCode,
+    decrease: KeyCode,
+    /// Change per second.
+    speed: f32,
+    value: fn(&mut FakeInteriorMaterial) -> &mut f32,
+}
+
+const PARAMS: &[Param] = &[
+    Param {
+        name: "depth",
+        increase: KeyCode::KeyQ,
+        decrease: KeyCode::KeyA,
+        speed: 0.2,
+        value: |m| &mut m.depth,
+    },
+    Param {
+        name: "room_seed",
+        increase: KeyCode::KeyW,
+        decrease: KeyCode::KeyS,
+        speed: 0.5,
+        value: |m| &mut m.room_seed,
+    },
+    Param {
+        name: "emission_seed",
+        increase: KeyCode::KeyE,
+        decrease: KeyCode::KeyD,
+        speed: 0.5,
+        value: |m| &mut m.emission_seed,
+    },
+    Param {
+        name: "emission_threshold",
+        increase: KeyCode::KeyR,
+        decrease: KeyCode::KeyF,
+        speed: 0.2,
+        value: |m| &mut m.emission_threshold,
+    },
+    Param {
+        name: "depth_variation",
+        increase: KeyCode::KeyT,
+        decrease: KeyCode::KeyG,
+        speed: 0.1,
+        value: |m| &mut m.depth_variation,
+    },
+];
+
+/// set up a wall, a light, a camera and the help text
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    let mut interior = FakeInteriorMaterial::from_paths(
+        &asset_server,
+        "textures/rooms_depth.png",
+        "textures/rooms_emit.png",
+        FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(4.0, 4.0),
+            depth: 0.5,
+            room_seed: 1.2,
+            emission_intensity: 10.0,
+            ..default()
+        },
+    );
+    interior.base.reflectance = 1.0;
+    let interior = interiors.add(interior);
+    commands.insert_resource(Tuned(interior.clone()));
+
+    spawn_fake_interior_wall(
+        &mut commands,
+        &mut meshes,
+        interior,
+        Vec2::splat(2.0),
+        Transform::IDENTITY,
+    );
+
+    // light
+    commands.spawn((
+        PointLight {
+            intensity: 1500.0,
+            ..default()
+        },
+        Transform::from_xyz(2.0, 3.0, 4.0),
+    ));
+
+    // camera, off-axis to show the parallax of the rooms.
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.8, 0.3, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        ParamsText,
+    ));
+}
+
+/// change the parameters of the held down keys
+fn tune(
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    tuned: Res<Tuned>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+) {
+    let step = |param: &Param| {
+        let up = keys.pressed(param.increase) as i32 as f32;
+        let down = keys.pressed(param.decrease) as i32 as f32;
+        (up - down) * param.speed * time.delta_secs()
+    };
+    let rooms_step = keys.just_pressed(KeyCode::KeyY) as i32 as f32
+        - keys.just_pressed(KeyCode::KeyH) as i32 as f32;
+    let toggle_atlas_debug = keys.just_pressed(KeyCode::KeyU);
+    if rooms_step == 0.0 && !toggle_atlas_debug && PARAMS.iter().all(|param| step(param) == 0.0) {
+        return;
+    }
+
+    let Some(interior) = interiors.get_mut(&tuned.0) else {
+        return;
+    };
+    let material = &mut interior.extension;
+    for param in PARAMS {
+        *(param.value)(material) += step(param);
+    }
+    material.depth = material.depth.clamp(0.0, 0.99);
+    material.emission_threshold = material.emission_threshold.clamp(0.0, 1.0);
+    material.depth_variation = material.depth_variation.max(0.0);
+    material.rooms = (material.rooms + rooms_step).max(Vec2::ONE);
+    if toggle_atlas_debug {
+        material.atlas_debug ^= 1;
+    }
+}
+
+/// show the keys and the current values
+fn show_params(
+    tuned: Res<Tuned>,
+    interiors: Res<Assets<StandardFakeInteriorMaterial>>,
+    mut text: Single<&mut Text, With<ParamsText>>,
+) {
+    if !interiors.is_changed() {
+        return;
+    }
+    let Some(interior) = interiors.get(&tuned.0) else {
+        return;
+    };
+    let mut material = interior.extension.clone();
+    let mut lines: Vec<String> = PARAMS
+        .iter()
+        .map(|param| {
+            format!(
+                "{:?}/{:?}: {} = {:.2}",
+                param.increase,
+                param.decrease,
+                param.name,
+                (param.value)(&mut material)
+            )
+        })
+        .collect();
+    lines.push(format!(
+        "KeyY/KeyH: rooms = {}x{}",
+        material.rooms.x, material.rooms.y
+    ));
+    lines.push(format!("KeyU: atlas_debug = {}", material.atlas_debug));
+    text.0 = lines.join("\n");
+}
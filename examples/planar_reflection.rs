@@ -0,0 +1,206 @@
+//! Planar reflections of the street in the windows with `planar_reflection_texture`.
+//!
+//! A second camera mirrored by the wall's plane renders the street into an image every
+//! frame, the material blends it into the glass by `glass_reflectance`. The image has the
+//! size of the window, the shader samples it at each fragment's screen position. Rendering
+//! the scene twice is what makes planar reflections expensive, use them for a few hero walls.
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+    },
+};
+
+use bevy_fake_interior::*;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "Planar reflection".into(),
+            ..default()
+        }),
+        ..default()
+    }));
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (orbit_camera, mirror_camera, match_window_size).chain(),
+        );
+
+    app.run();
+}
+
+/// The camera looking at the wall.
+#[derive(Component)]
+struct MainCamera;
+
+/// The camera rendering the reflection, mirrored by the wall's plane (`z = 0`).
+#[derive(Component)]
+struct ReflectionCamera;
+
+/// Image the reflection is rendered into.
+#[derive(Resource)]
+struct Reflection(Handle<Image>);
+
+/// Mirror `v` by the wall's plane.
+fn mirror(v: Vec3) -> Vec3 {
+    Vec3::new(v.x, v.y, -v.z)
+}
+
+/// set up a wall with reflecting windows, a street with a few objects to reflect, a light
+/// and the two cameras
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    // resized to the window by `match_window_size`.
+    let mut reflection = Image::new_fill(
+        Extent3d {
+            width: 16,
+            height: 16,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    reflection.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    let reflection = images.add(reflection);
+
+    let interior = interiors.add(StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(6.0, 4.0),
+            depth: 0.5,
+            room_seed: 1.2,
+            planar_reflection_texture: Some(reflection.clone()),
+            glass_reflectance: 0.35,
+            ..default()
+        },
+    });
+
+    // building front, facing +Z at `z = 0`.
+    spawn_fake_interior_wall(
+        &mut commands,
+        &mut meshes,
+        interior,
+        Vec2::new(6.0, 4.0),
+        Transform::from_xyz(0.0, 2.0, 0.0),
+    );
+
+    // street in front of it.
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(12.0, 12.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.3, 0.32))),
+        Transform::from_xyz(0.0, 0.0, 6.0),
+    ));
+
+    // things to see in the windows.
+    let ball = meshes.add(Sphere::new(0.5));
+    for (i, color) in [
+        Color::srgb(0.9, 0.2, 0.1),
+        Color::srgb(0.1, 0.7, 0.2),
+        Color::srgb(0.2, 0.3, 0.9),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        commands.spawn((
+            Mesh3d(ball.clone()),
+            MeshMaterial3d(materials.add(color)),
+            Transform::from_xyz(i as f32 * 2.0 - 2.0, 0.5, 3.0),
+        ));
+    }
+
+    // light
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 3000.0,
+            ..default()
+        },
+        Transform::from_xyz(-2.0, 4.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // cameras
+    commands.insert_resource(Reflection(reflection.clone()));
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.5, 9.0).looking_at(Vec3::new(0.0, 1.5, 0.0), Vec3::Y),
+        MainCamera,
+    ));
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            target: RenderTarget::Image(reflection),
+            // render the reflection before the main camera samples it.
+            order: -1,
+            ..default()
+        },
+        ReflectionCamera,
+    ));
+}
+
+/// slowly swing the camera in front of the wall
+fn orbit_camera(time: Res<Time>, mut camera: Query<&mut Transform, With<MainCamera>>) {
+    let angle = (time.elapsed_secs() * 0.3).sin() * 0.6;
+    let position = Vec3::new(angle.sin() * 9.0, 1.5, angle.cos() * 9.0);
+    *camera.single_mut() =
+        Transform::from_translation(position).looking_at(Vec3::new(0.0, 1.5, 0.0), Vec3::Y);
+}
+
+/// put the reflection camera at the mirror image of the main camera
+fn mirror_camera(
+    main: Query<&Transform, (With<MainCamera>, Without<ReflectionCamera>)>,
+    mut reflection: Query<&mut Transform, With<ReflectionCamera>>,
+) {
+    let main = main.single();
+    // mirroring flips the handedness, the shader flips the image back.
+    *reflection.single_mut() = Transform::from_translation(mirror(main.translation))
+        .looking_to(mirror(*main.forward()), mirror(*main.up()));
+}
+
+/// keep the reflection the size of the window, it's sampled at screen positions
+fn match_window_size(
+    windows: Query<&Window>,
+    reflection: Res<Reflection>,
+    mut images: ResMut<Assets<Image>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let size = window.physical_size().max(UVec2::ONE);
+    let Some(image) = images.get(&reflection.0) else {
+        return;
+    };
+    if image.size() != size {
+        if let Some(image) = images.get_mut(&reflection.0) {
+            image.resize(Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            });
+        }
+        // the material bind groups hold the old texture, mark them modified to rebuild them.
+        for _ in interiors.iter_mut() {}
+    }
+}
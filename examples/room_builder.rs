@@ -49,7 +49,7 @@ fn main() {
         prepass_enabled: false,
         ..default()
     });
-    app.add_plugins(FakeInteriorMaterialPlugin)
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
         .add_plugins(
             bevy_inspector_egui::quick::WorldInspectorPlugin::new()
                 .run_if(common_conditions::input_toggle_active(false, KeyCode::KeyE)),
@@ -1,4 +1,8 @@
 //! Room texture builder.
+//!
+//! Space saves a screenshot of the color. X exports an atlas: the color, the depth prepass
+//! and the normal prepass, each a screenshot from the same camera so the three PNGs are
+//! aligned, for rooms using `depth_map` and `normal_map_texture` too.
 
 use bevy::{
     core_pipeline::prepass::{DepthPrepass, NormalPrepass},
@@ -48,7 +52,7 @@ fn main() {
         prepass_enabled: false,
         ..default()
     });
-    app.add_plugins(FakeInteriorMaterialPlugin)
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
         .add_plugins(
             bevy_inspector_egui::quick::WorldInspectorPlugin::new()
                 .run_if(common_conditions::input_toggle_active(false, KeyCode::KeyE)),
@@ -57,12 +61,16 @@ fn main() {
 
     app.insert_resource(PointLightShadowMap { size: 4096 });
 
+    app.init_resource::<AtlasExport>();
+
     app.add_systems(Startup, (setup, setup_room)).add_systems(
         Update,
         (
             handle_quit,
             toggle_prepass_view.run_if(common_conditions::input_just_pressed(KeyCode::KeyP)),
             screenshot_on_spacebar.run_if(common_conditions::input_just_pressed(KeyCode::Space)),
+            start_atlas_export.run_if(common_conditions::input_just_pressed(KeyCode::KeyX)),
+            export_atlas,
         ),
     );
 
@@ -99,26 +107,56 @@ impl Material for PrepassOutputMaterial {
     }
 }
 
-/// Every time you press space, it will cycle between transparent, depth and normals view
+/// What the prepass quad shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum PrepassView {
+    /// Nothing, the scene is seen through the quad.
+    #[default]
+    Transparent,
+    Depth,
+    Normals,
+}
+
+impl PrepassView {
+    fn next(self) -> Self {
+        match self {
+            Self::Transparent => Self::Depth,
+            Self::Depth => Self::Normals,
+            Self::Normals => Self::Transparent,
+        }
+    }
+
+    /// Suffix of the exported atlas image.
+    fn atlas_suffix(self) -> &'static str {
+        match self {
+            Self::Transparent => "color",
+            Self::Depth => "depth",
+            Self::Normals => "normal",
+        }
+    }
+}
+
+fn set_prepass_view(
+    view: PrepassView,
+    material_handle: &Query<&MeshMaterial3d<PrepassOutputMaterial>>,
+    materials: &mut Assets<PrepassOutputMaterial>,
+) {
+    let handle = material_handle.single();
+    let mat = materials.get_mut(handle).unwrap();
+    mat.settings.show_depth = (view == PrepassView::Depth) as u32;
+    mat.settings.show_normals = (view == PrepassView::Normals) as u32;
+}
+
+/// Every time you press P, it will cycle between transparent, depth and normals view
 fn toggle_prepass_view(
-    mut prepass_view: Local<u32>,
+    mut prepass_view: Local<PrepassView>,
     material_handle: Query<&MeshMaterial3d<PrepassOutputMaterial>>,
     mut materials: ResMut<Assets<PrepassOutputMaterial>>,
 ) {
-    *prepass_view = (*prepass_view + 1) % 3;
-
-    let label = match *prepass_view {
-        0 => "transparent",
-        1 => "depth",
-        2 => "normals",
-        _ => unreachable!(),
-    };
-    eprintln!("Prepass Output: {label}");
+    *prepass_view = prepass_view.next();
+    eprintln!("Prepass Output: {:?}", *prepass_view);
 
-    let handle = material_handle.single();
-    let mat = materials.get_mut(handle).unwrap();
-    mat.settings.show_depth = (*prepass_view == 1) as u32;
-    mat.settings.show_normals = (*prepass_view == 2) as u32;
+    set_prepass_view(*prepass_view, &material_handle, &mut materials);
 }
 
 fn screenshot_on_spacebar(mut commands: Commands, mut counter: Local<u32>) {
@@ -129,6 +167,69 @@ fn screenshot_on_spacebar(mut commands: Commands, mut counter: Local<u32>) {
         .observe(save_to_disk(path));
 }
 
+/// Frames to wait after changing the view, so the screenshot sees the updated material.
+const EXPORT_SETTLE_FRAMES: u32 = 3;
+
+/// Export of the color, depth and normal atlases in progress.
+#[derive(Resource, Default)]
+struct AtlasExport {
+    /// Number of the next export.
+    counter: u32,
+    /// View to capture next and the frames left to wait before capturing it.
+    next: Option<(PrepassView, u32)>,
+    /// Go back to the transparent view once the last screenshot was taken.
+    restore: bool,
+}
+
+fn start_atlas_export(mut export: ResMut<AtlasExport>) {
+    if export.next.is_none() {
+        export.next = Some((PrepassView::Transparent, EXPORT_SETTLE_FRAMES));
+    }
+}
+
+/// Capture the views one after the other, then go back to the transparent view.
+fn export_atlas(
+    mut commands: Commands,
+    mut export: ResMut<AtlasExport>,
+    material_handle: Query<&MeshMaterial3d<PrepassOutputMaterial>>,
+    mut materials: ResMut<Assets<PrepassOutputMaterial>>,
+) {
+    let Some((view, wait)) = export.next else {
+        if export.restore {
+            export.restore = false;
+            set_prepass_view(PrepassView::Transparent, &material_handle, &mut materials);
+        }
+        return;
+    };
+    if wait == EXPORT_SETTLE_FRAMES {
+        set_prepass_view(view, &material_handle, &mut materials);
+    }
+    if wait > 0 {
+        export.next = Some((view, wait - 1));
+        return;
+    }
+
+    let path = format!(
+        "./assets/textures/atlas-{}_{}.png",
+        export.counter,
+        view.atlas_suffix()
+    );
+    eprintln!("Export: {path}");
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(path));
+
+    export.next = match view.next() {
+        PrepassView::Transparent => {
+            // the screenshot renders this frame, keep the view until the next one.
+            export.counter += 1;
+            export.restore = true;
+            None
+        }
+        next => Some((next, EXPORT_SETTLE_FRAMES)),
+    };
+}
+
 /// set up a simple 3D scene
 fn setup_room(
     mut commands: Commands,
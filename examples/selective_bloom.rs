@@ -0,0 +1,119 @@
+//! Bloom only the glowing windows, not the rest of the scene.
+//!
+//! The wall is drawn twice: the main camera sees it without its glow, and a copy with only
+//! the glow is on `GLOW_LAYER`, seen by a glow camera with `Bloom` that adds its output on
+//! top of the main camera's. The bright sphere in front of the wall doesn't bloom.
+
+use bevy::{core_pipeline::bloom::Bloom, prelude::*, render::view::RenderLayers};
+
+use bevy_fake_interior::*;
+
+/// Render layer of the glow copies of the walls.
+const GLOW_LAYER: usize = 1;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Selective bloom".into(),
+                    ..default()
+                }),
+                ..default()
+            })
+            .set(AssetPlugin {
+                mode: AssetMode::Processed,
+                ..default()
+            }),
+    );
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin)
+        .add_systems(Startup, setup);
+
+    app.run();
+}
+
+/// set up a wall with its glow copy, a bright sphere and the two cameras
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    let interior = StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
+            reflectance: 1.0,
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(6.0, 4.0),
+            depth: 0.5,
+            room_seed: 1.2,
+            emission_intensity: 20.0,
+            ..default()
+        },
+    };
+    let (main, glow) = split_fake_interior_glow(&interior);
+
+    // the wall and its glow copy, sharing the mesh.
+    let mesh = meshes.add(fake_interior_wall_mesh(Vec2::new(6.0, 4.0)));
+    commands
+        .spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(interiors.add(main)),
+            Transform::from_xyz(0.0, 2.0, 0.0),
+            Name::new("Wall"),
+        ))
+        .with_child((
+            Mesh3d(mesh),
+            MeshMaterial3d(interiors.add(glow)),
+            RenderLayers::layer(GLOW_LAYER),
+        ));
+
+    // bright, but not on the glow layer, so it doesn't bloom.
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(0.5))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::WHITE,
+            emissive: LinearRgba::rgb(4.0, 3.0, 1.0),
+            ..default()
+        })),
+        Transform::from_xyz(-2.0, 0.5, 3.0),
+        Name::new("Sphere"),
+    ));
+
+    // moon light
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 50.0,
+            ..default()
+        },
+        Transform::from_xyz(-2.0, 4.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // main camera, not HDR so it doesn't share its textures with the glow camera.
+    commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 1.5, 9.0).looking_at(Vec3::new(0.0, 2.0, 0.0), Vec3::Y),
+            bevy_panorbit_camera::PanOrbitCamera {
+                focus: Vec3::new(0.0, 2.0, 0.0),
+                radius: Some(9.0),
+                ..default()
+            },
+            Name::new("Camera"),
+        ))
+        .with_child((
+            fake_interior_glow_camera(1, RenderLayers::layer(GLOW_LAYER)),
+            Bloom::NATURAL,
+            Name::new("Glow camera"),
+        ));
+}
@@ -28,7 +28,7 @@ fn main() {
         FrameTimeDiagnosticsPlugin,
     ));
 
-    app.add_plugins(FakeInteriorMaterialPlugin)
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
         .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
         .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin)
         .add_plugins(bevy_spectator::SpectatorPlugin)
@@ -55,7 +55,7 @@ fn setup(
     let _interior1 = MeshMaterial3d(interiors.add(StandardFakeInteriorMaterial {
         base: StandardMaterial {
             base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
-            emissive: LinearRgba::WHITE * 10.0,
+            emissive: LinearRgba::WHITE,
             emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
             reflectance: 1.0,
             ..default()
@@ -65,13 +65,14 @@ fn setup(
             rooms: Vec2::new(6.0, 6.0),
             depth: 0.5,
             room_seed: 1.2,
+            emission_intensity: 10.0,
             ..default()
         },
     }));
     let _test_room = MeshMaterial3d(interiors.add(StandardFakeInteriorMaterial {
         base: StandardMaterial {
             base_color_texture: Some(asset_server.load("textures/test_room.png")),
-            emissive: LinearRgba::WHITE * 10.0,
+            emissive: LinearRgba::WHITE,
             emissive_texture: Some(asset_server.load("textures/test_room_E.png")),
             reflectance: 1.0,
             ..default()
@@ -81,13 +82,14 @@ fn setup(
             rooms: Vec2::new(6.0, 6.0),
             depth: 0.5,
             room_seed: 1.2,
+            emission_intensity: 10.0,
             ..default()
         },
     }));
     let interior2 = MeshMaterial3d(interiors.add(StandardFakeInteriorMaterial {
         base: StandardMaterial {
             base_color_texture: Some(asset_server.load("textures/room_3.png")),
-            emissive: LinearRgba::WHITE * 10.0,
+            emissive: LinearRgba::WHITE,
             emissive_texture: Some(asset_server.load("textures/room_3_E.png")),
             reflectance: 1.0,
             ..default()
@@ -97,6 +99,7 @@ fn setup(
             rooms: Vec2::new(6.0, 6.0),
             depth: 0.5,
             room_seed: 1.4,
+            emission_intensity: 10.0,
             ..default()
         },
     }));
@@ -0,0 +1,154 @@
+//! A fake interior on a skinned facade swaying back and forth.
+//!
+//! The interior uses the normal and tangent that Bevy's mesh vertex shader outputs after
+//! skinning (and morph targets), so the rooms follow the bent wall.
+
+use bevy::{
+    prelude::*,
+    render::mesh::{
+        skinning::{SkinnedMesh, SkinnedMeshInverseBindposes},
+        VertexAttributeValues,
+    },
+};
+
+use bevy_fake_interior::*;
+
+/// Height of the facade, the upper joint is half way up.
+const HEIGHT: f32 = 4.0;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Skinned".into(),
+                    ..default()
+                }),
+                ..default()
+            })
+            .set(AssetPlugin {
+                mode: AssetMode::Processed,
+                ..default()
+            }),
+    );
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(Update, sway);
+
+    app.run();
+}
+
+/// The joint bending the upper half of the facade.
+#[derive(Component)]
+struct SwayJoint;
+
+/// Subdivided facade standing on the origin, facing `+Z`, skinned to a joint at its base
+/// and one half way up.
+fn skinned_facade_mesh() -> Mesh {
+    let mut mesh = Plane3d::new(Vec3::Z, Vec2::new(1.5, HEIGHT / 2.0))
+        .mesh()
+        .subdivisions(8)
+        .build()
+        .translated_by(Vec3::Y * HEIGHT / 2.0)
+        .with_generated_tangents()
+        .expect("plane mesh has positions, normals and UVs");
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        unreachable!("plane meshes have positions");
+    };
+    // blend from the base joint to the upper joint around the middle.
+    let weights: Vec<[f32; 4]> = positions
+        .iter()
+        .map(|&[_, y, _]| {
+            let upper = ((y - HEIGHT * 0.25) / (HEIGHT * 0.5)).clamp(0.0, 1.0);
+            [1.0 - upper, upper, 0.0, 0.0]
+        })
+        .collect();
+    let joints = vec![[0u16, 1, 0, 0]; weights.len()];
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_JOINT_INDEX,
+        VertexAttributeValues::Uint16x4(joints),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT, weights);
+    mesh
+}
+
+/// set up the skinned facade, a light and a camera
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    mut inverse_bindposes: ResMut<Assets<SkinnedMeshInverseBindposes>>,
+    asset_server: Res<AssetServer>,
+) {
+    let interior = interiors.add(StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
+            reflectance: 1.0,
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(3.0, 4.0),
+            depth: 0.5,
+            room_seed: 1.2,
+            emission_intensity: 10.0,
+            ..default()
+        },
+    });
+
+    // the joints at their bind poses, the upper one sways.
+    let base_joint = commands.spawn(Transform::IDENTITY).id();
+    let sway_joint = commands
+        .spawn((SwayJoint, Transform::from_xyz(0.0, HEIGHT / 2.0, 0.0)))
+        .id();
+    commands.entity(base_joint).add_child(sway_joint);
+    let inverse_bindposes = inverse_bindposes.add(vec![
+        Mat4::IDENTITY,
+        Mat4::from_translation(Vec3::new(0.0, -HEIGHT / 2.0, 0.0)),
+    ]);
+
+    commands.spawn((
+        Mesh3d(meshes.add(skinned_facade_mesh())),
+        MeshMaterial3d(interior),
+        SkinnedMesh {
+            inverse_bindposes,
+            joints: vec![base_joint, sway_joint],
+        },
+        Name::new("Facade"),
+    ));
+
+    // light
+    commands.spawn((
+        PointLight {
+            intensity: 1500.0,
+            ..default()
+        },
+        Transform::from_xyz(2.0, 3.0, 4.0),
+    ));
+
+    // camera
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(3.0, 2.0, 6.0).looking_at(Vec3::new(0.0, 2.0, 0.0), Vec3::Y),
+        bevy_panorbit_camera::PanOrbitCamera {
+            focus: Vec3::new(0.0, 2.0, 0.0),
+            radius: Some(7.0),
+            ..default()
+        },
+    ));
+}
+
+/// bend the upper half of the facade back and forth
+fn sway(time: Res<Time>, mut joints: Query<&mut Transform, With<SwayJoint>>) {
+    for mut transform in &mut joints {
+        transform.rotation = Quat::from_rotation_x(0.4 * time.elapsed_secs().sin());
+    }
+}
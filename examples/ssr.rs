@@ -0,0 +1,128 @@
+//! Glowing windows reflected in a glossy floor with `ScreenSpaceReflections`.
+//!
+//! SSR needs the deferred renderer: the camera gets a `DeferredPrepass` (required by
+//! `ScreenSpaceReflections`), MSAA is off, and the opaque materials render deferred so the
+//! floor's roughness is in the G-buffer. The fake interior walls write their interior color,
+//! emissive and normal to the G-buffer like any other material, so keep `prepass_enabled`
+//! on in the `FakeInteriorMaterialPlugin`. An HDR camera keeps the window glow above `1.0`
+//! for the reflections.
+
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    pbr::{DefaultOpaqueRendererMethod, ScreenSpaceReflections},
+    prelude::*,
+};
+
+use bevy_fake_interior::*;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins((
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Screen space reflections".into(),
+                    ..default()
+                }),
+                ..default()
+            })
+            .set(AssetPlugin {
+                mode: AssetMode::Processed,
+                ..default()
+            }),
+        LogDiagnosticsPlugin::default(),
+        FrameTimeDiagnosticsPlugin,
+    ));
+
+    app.insert_resource(DefaultOpaqueRendererMethod::deferred())
+        .insert_resource(AmbientLight {
+            color: Color::WHITE,
+            brightness: 20.0,
+        });
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
+        .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin)
+        .add_systems(Startup, setup);
+
+    app.run();
+}
+
+/// set up a building front at night with a wet street in front of it
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    let interior = interiors.add(StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
+            reflectance: 1.0,
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(6.0, 4.0),
+            depth: 0.5,
+            room_seed: 1.2,
+            emission_intensity: 20.0,
+            ..default()
+        },
+    });
+
+    // building front
+    let wall = spawn_fake_interior_wall(
+        &mut commands,
+        &mut meshes,
+        interior,
+        Vec2::new(6.0, 4.0),
+        Transform::from_xyz(0.0, 2.0, 0.0),
+    );
+    commands.entity(wall).insert(Name::new("Wall"));
+
+    // glossy street, below the `perceptual_roughness_threshold` of the reflections.
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(12.0, 12.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.05, 0.05, 0.06),
+            perceptual_roughness: 0.05,
+            ..default()
+        })),
+        Transform::from_xyz(0.0, 0.0, 6.0),
+        Name::new("Street"),
+    ));
+
+    // moon light
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 50.0,
+            ..default()
+        },
+        Transform::from_xyz(-2.0, 4.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // camera, low over the street to see the reflections.
+    let mut cam = commands.spawn((
+        Camera3d::default(),
+        Camera {
+            hdr: true,
+            ..default()
+        },
+        Msaa::Off,
+        ScreenSpaceReflections::default(),
+        Transform::from_xyz(0.0, 0.6, 9.0).looking_at(Vec3::new(0.0, 1.5, 0.0), Vec3::Y),
+    ));
+    cam.insert(bevy_panorbit_camera::PanOrbitCamera {
+        focus: Vec3::new(0.0, 1.5, 0.0),
+        radius: Some(9.0),
+        yaw: Some(0.0),
+        pitch: Some(0.1),
+        ..default()
+    });
+    cam.insert(Name::new("Camera"));
+}
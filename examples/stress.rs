@@ -0,0 +1,112 @@
+//! Many fake interior walls sharing one material, logging their diagnostics.
+//!
+//! Needs the `diagnostics` feature: `cargo run --example stress --features diagnostics`.
+//! Press H to hide the walls and compare `fake_interior/scene_passes_gpu` with and without them.
+
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    input::common_conditions,
+    prelude::*,
+};
+
+use bevy_fake_interior::*;
+
+/// Walls along each side of the grid.
+const GRID: i32 = 40;
+/// Distance between the walls of the grid.
+const SPACING: f32 = 6.0;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins((
+        DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Stress".into(),
+                ..default()
+            }),
+            ..default()
+        }),
+        FrameTimeDiagnosticsPlugin,
+        LogDiagnosticsPlugin {
+            filter: Some(vec![
+                FrameTimeDiagnosticsPlugin::FRAME_TIME,
+                FakeInteriorDiagnostics::VISIBLE_WALLS,
+                FakeInteriorDiagnostics::SCENE_PASSES_GPU,
+            ]),
+            ..default()
+        },
+    ));
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            toggle_walls.run_if(common_conditions::input_just_pressed(KeyCode::KeyH)),
+        );
+
+    app.run();
+}
+
+/// Every time you press H, it will hide or show the walls
+fn toggle_walls(
+    mut walls: Query<&mut Visibility, With<MeshMaterial3d<StandardFakeInteriorMaterial>>>,
+) {
+    for mut visibility in &mut walls {
+        visibility.toggle_visible_hidden();
+    }
+}
+
+/// set up a grid of walls, a light and the camera
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    let mesh = meshes.add(fake_interior_wall_mesh(Vec2::new(5.0, 5.0)));
+    let interior = interiors.add(FakeInteriorMaterial::from_paths(
+        &asset_server,
+        "textures/rooms_depth.png",
+        "textures/rooms_emit.png",
+        FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(6.0, 6.0),
+            depth: 0.5,
+            emission_intensity: 10.0,
+            ..default()
+        },
+    ));
+
+    // walls, all facing the camera.
+    for x in 0..GRID {
+        for y in 0..GRID {
+            commands.spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(interior.clone()),
+                Transform::from_xyz(
+                    (x - GRID / 2) as f32 * SPACING,
+                    (y - GRID / 2) as f32 * SPACING,
+                    0.0,
+                ),
+            ));
+        }
+    }
+
+    // light
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 2000.0,
+            ..default()
+        },
+        Transform::from_xyz(2.0, 4.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // camera
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 150.0).looking_at(Vec3::ZERO, Vec3::Y),
+        bevy_panorbit_camera::PanOrbitCamera::default(),
+    ));
+}
@@ -49,7 +49,7 @@ fn main() {
         prepass_enabled: false,
         ..default()
     });
-    app.add_plugins(FakeInteriorMaterialPlugin)
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
         .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
         .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin);
 
@@ -342,7 +342,7 @@ fn setup(
     let interior1 = interiors.add(StandardFakeInteriorMaterial {
         base: StandardMaterial {
             base_color_texture: Some(asset_server.load("textures/room_gltf.png")),
-            emissive: LinearRgba::WHITE * 15.0,
+            emissive: LinearRgba::WHITE,
             emissive_texture: Some(asset_server.load("textures/room_gltf_E.png")),
             reflectance: 0.2,
             ..default()
@@ -352,6 +352,7 @@ fn setup(
             rooms: Vec2::new(1.0, 1.0),
             depth: 0.5,
             room_seed: 1.2,
+            emission_intensity: 15.0,
             ..default()
         },
     });
@@ -359,7 +360,7 @@ fn setup(
         base: StandardMaterial {
             //perceptual_roughness: 0.4,
             base_color_texture: Some(asset_server.load("textures/room_gltf.png")),
-            emissive: LinearRgba::WHITE * 15.0,
+            emissive: LinearRgba::WHITE,
             emissive_texture: Some(asset_server.load("textures/room_gltf_E.png")),
             normal_map_texture: Some(asset_server.load("textures/room_gltf_normal.png")),
             reflectance: 0.2,
@@ -370,6 +371,7 @@ fn setup(
             rooms: Vec2::new(1.0, 1.0),
             depth: 0.5,
             room_seed: 1.2,
+            emission_intensity: 15.0,
             ..default()
         },
     });
@@ -377,7 +379,7 @@ fn setup(
         base: StandardMaterial {
             //perceptual_roughness: 0.4,
             base_color_texture: Some(asset_server.load("textures/room_gltf.png")),
-            emissive: LinearRgba::WHITE * 15.0,
+            emissive: LinearRgba::WHITE,
             emissive_texture: Some(asset_server.load("textures/room_gltf_E.png")),
             normal_map_texture: Some(asset_server.load("textures/room_gltf_normal.png")),
             depth_map: Some(asset_server.load("textures/room_gltf_depth.png")),
@@ -390,9 +392,14 @@ fn setup(
             rooms: Vec2::new(1.0, 1.0),
             depth: 0.5,
             room_seed: 1.2,
+            emission_intensity: 15.0,
             ..default()
         },
     });
+    // mirroring turns the triangles' winding around and Bevy doesn't flip the culling for it.
+    let mut mirrored = interiors.get(&interior_n).unwrap().clone();
+    mirrored.base.cull_mode = None;
+    let interior_mirrored = interiors.add(mirrored);
 
     let mesh = Mesh3d(
         meshes.add(
@@ -424,6 +431,15 @@ fn setup(
         MeshMaterial3d(interior_d),
     ));
     wall.insert(Name::new("Wall 3"));
+    // wall 4, mirrored: the rooms must not turn inside out.
+    let mut wall = commands.spawn((
+        mesh.clone(),
+        Transform::from_xyz(20.0, 0.0, 5.0)
+            .with_rotation(Quat::from_rotation_x(1.570796))
+            .with_scale(Vec3::new(-1.0, 1.0, 1.0)),
+        MeshMaterial3d(interior_mirrored),
+    ));
+    wall.insert(Name::new("Wall 4 (mirrored)"));
     /*
     // window 1
     let mut window = commands.spawn((
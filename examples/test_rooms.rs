@@ -41,7 +41,7 @@ fn main() {
         prepass_enabled: false,
         ..default()
     });
-    app.add_plugins(FakeInteriorMaterialPlugin)
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
         .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
         .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin);
 
@@ -51,6 +51,7 @@ fn main() {
         (
             handle_quit,
             toggle_prepass_view.run_if(common_conditions::input_just_pressed(KeyCode::KeyP)),
+            toggle_normal_maps.run_if(common_conditions::input_just_pressed(KeyCode::KeyN)),
         ),
     );
 
@@ -109,6 +110,13 @@ fn toggle_prepass_view(
     mat.settings.show_normals = (*prepass_view == 2) as u32;
 }
 
+/// Every time you press N, it will switch the normal maps of the rooms off or on
+fn toggle_normal_maps(mut materials: ResMut<Assets<StandardFakeInteriorMaterial>>) {
+    for (_, material) in materials.iter_mut() {
+        material.extension.use_normal_map = (material.extension.use_normal_map == 0) as u32;
+    }
+}
+
 /// set up a simple 3D scene
 fn setup(
     mut commands: Commands,
@@ -148,7 +156,7 @@ fn setup(
     let room_01 = interiors.add(StandardFakeInteriorMaterial {
         base: StandardMaterial {
             base_color_texture: Some(asset_server.load("textures/room_gltf.png")),
-            emissive: LinearRgba::WHITE * 15.0,
+            emissive: LinearRgba::WHITE,
             emissive_texture: Some(asset_server.load("textures/room_gltf_E.png")),
             reflectance: 0.2,
             normal_map_texture: Some(asset_server.load("textures/room_gltf_normal.png")),
@@ -161,13 +169,14 @@ fn setup(
             rooms: Vec2::new(1.0, 1.0),
             depth: 0.5,
             room_seed: 1.2,
+            emission_intensity: 15.0,
             ..default()
         },
     });
     let room_02 = interiors.add(StandardFakeInteriorMaterial {
         base: StandardMaterial {
             base_color_texture: Some(asset_server.load("textures/room_gltf_02.png")),
-            emissive: LinearRgba::WHITE * 15.0,
+            emissive: LinearRgba::WHITE,
             emissive_texture: Some(asset_server.load("textures/room_gltf_02_E.png")),
             reflectance: 0.2,
             normal_map_texture: Some(asset_server.load("textures/room_gltf_02_normal.png")),
@@ -180,13 +189,14 @@ fn setup(
             rooms: Vec2::new(1.0, 1.0),
             depth: 0.5,
             room_seed: 1.2,
+            emission_intensity: 15.0,
             ..default()
         },
     });
     let room_03 = interiors.add(StandardFakeInteriorMaterial {
         base: StandardMaterial {
             base_color_texture: Some(asset_server.load("textures/room_gltf_02.png")),
-            emissive: LinearRgba::WHITE * 15.0,
+            emissive: LinearRgba::WHITE,
             emissive_texture: Some(asset_server.load("textures/room_gltf_02_E.png")),
             reflectance: 0.2,
             //normal_map_texture: Some(asset_server.load("textures/room_gltf_02_normal.png")),
@@ -199,6 +209,7 @@ fn setup(
             rooms: Vec2::new(1.0, 1.0),
             depth: 0.5,
             room_seed: 1.2,
+            emission_intensity: 15.0,
             ..default()
         },
     });
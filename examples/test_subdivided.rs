@@ -0,0 +1,136 @@
+//! Fake interiors on subdivided meshes: a flat and a curved wall with many vertices.
+//!
+//! The interpolated normals and tangents of these meshes aren't unit length or
+//! perpendicular, the rooms should still have straight edges and stay still while orbiting.
+
+use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+
+use bevy_fake_interior::*;
+
+/// Subdivisions of the walls along each side.
+const SUBDIVISIONS: u32 = 16;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Test subdivided".into(),
+                    ..default()
+                }),
+                ..default()
+            })
+            .set(AssetPlugin {
+                mode: AssetMode::Processed,
+                ..default()
+            }),
+    );
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin)
+        .add_systems(Startup, setup);
+
+    app.run();
+}
+
+/// Subdivided plane of `size` facing `+Z`.
+fn subdivided_wall_mesh(size: Vec2) -> Mesh {
+    Plane3d::new(Vec3::Z, size / 2.0)
+        .mesh()
+        .subdivisions(SUBDIVISIONS)
+        .build()
+}
+
+/// Bend a `+Z` facing plane mesh around a vertical axis at `radius` behind it.
+fn bend_mesh(mut mesh: Mesh, radius: f32) -> Mesh {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        unreachable!("plane meshes have positions");
+    };
+    let (positions, normals): (Vec<[f32; 3]>, Vec<[f32; 3]>) = positions
+        .iter()
+        .map(|&[x, y, _]| {
+            let angle = x / radius;
+            let normal = Vec3::new(angle.sin(), 0.0, angle.cos());
+            let position = normal * radius - Vec3::Z * radius + Vec3::Y * y;
+            (position.to_array(), normal.to_array())
+        })
+        .unzip();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh
+}
+
+/// set up a flat and a curved subdivided wall, a light and a camera
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    let interior = interiors.add(StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
+            reflectance: 1.0,
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(4.0, 4.0),
+            depth: 0.5,
+            room_seed: 1.2,
+            emission_intensity: 10.0,
+            ..default()
+        },
+    });
+
+    let size = Vec2::splat(3.0);
+    let walls = [
+        (
+            subdivided_wall_mesh(size),
+            Vec3::new(-2.0, 1.5, 0.0),
+            "Flat",
+        ),
+        (
+            bend_mesh(subdivided_wall_mesh(size), 2.0),
+            Vec3::new(2.0, 1.5, 0.0),
+            "Curved",
+        ),
+    ];
+    for (mesh, position, name) in walls {
+        let mesh = mesh
+            .with_generated_tangents()
+            .expect("plane mesh has positions, normals and UVs");
+        commands.spawn((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(interior.clone()),
+            Transform::from_translation(position),
+            Name::new(name),
+        ));
+    }
+
+    // light
+    commands.spawn((
+        PointLight {
+            intensity: 1500.0,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 3.0, 4.0),
+    ));
+
+    // camera
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.5, 7.0).looking_at(Vec3::new(0.0, 1.5, 0.0), Vec3::Y),
+        bevy_panorbit_camera::PanOrbitCamera {
+            focus: Vec3::new(0.0, 1.5, 0.0),
+            radius: Some(7.0),
+            ..default()
+        },
+    ));
+}
@@ -0,0 +1,118 @@
+//! Fake interior on a wall with 3x tiled UVs.
+
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+    render::mesh::*,
+};
+
+use bevy_fake_interior::*;
+use std::f32::consts::FRAC_PI_2;
+
+const UV_TILING: f32 = 3.0;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins((
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Tiled UVs".into(),
+                    ..default()
+                }),
+                ..default()
+            })
+            .set(AssetPlugin {
+                mode: AssetMode::Processed,
+                ..default()
+            }),
+        LogDiagnosticsPlugin::default(),
+        FrameTimeDiagnosticsPlugin,
+    ));
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
+        .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin)
+        .add_systems(Startup, setup);
+
+    app.run();
+}
+
+/// Build a plane mesh with its UVs scaled by `tiling`.
+fn tiled_plane(size: f32, tiling: f32) -> Mesh {
+    let mut mesh = PlaneMeshBuilder::from_length(size).subdivisions(0).build();
+    if let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0) {
+        for uv in uvs.iter_mut() {
+            uv[0] *= tiling;
+            uv[1] *= tiling;
+        }
+    }
+    mesh.with_generated_tangents().unwrap()
+}
+
+/// set up a simple 3D scene
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    let material = |uv_tiling: f32| StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
+            reflectance: 1.0,
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(6.0, 6.0),
+            depth: 0.5,
+            room_seed: 1.2,
+            uv_tiling: Vec2::splat(uv_tiling),
+            emission_intensity: 10.0,
+            ..default()
+        },
+    };
+
+    // wall with 0..1 UVs
+    commands.spawn((
+        Mesh3d(meshes.add(tiled_plane(2.0, 1.0))),
+        MeshMaterial3d(interiors.add(material(1.0))),
+        Transform::from_xyz(-1.1, 0.0, 0.0).with_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+        Name::new("Wall 0..1 UVs"),
+    ));
+    // wall with 0..3 UVs, should show the same room grid.
+    commands.spawn((
+        Mesh3d(meshes.add(tiled_plane(2.0, UV_TILING))),
+        MeshMaterial3d(interiors.add(material(UV_TILING))),
+        Transform::from_xyz(1.1, 0.0, 0.0).with_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+        Name::new("Wall 0..3 UVs"),
+    ));
+
+    // light
+    commands.spawn((
+        PointLight {
+            intensity: 1500.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 8.0, 4.0),
+    ));
+
+    // camera
+    let mut cam = commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    cam.insert(bevy_panorbit_camera::PanOrbitCamera {
+        focus: Vec3::ZERO,
+        radius: Some(5.0),
+        yaw: Some(0.00),
+        pitch: Some(0.0),
+        ..default()
+    });
+    cam.insert(Name::new("Camera"));
+}
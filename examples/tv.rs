@@ -0,0 +1,154 @@
+//! Playing TVs in the rooms: the emissive atlas is a procedural "video" streamed every frame.
+//!
+//! The frames are written with `StreamedImages`, which copies them into the existing GPU
+//! texture. The `Image` asset and the material don't change, so nothing is re-uploaded and
+//! the material's bind group isn't rebuilt. The example logs the asset events of the
+//! material and the image to show there are none after loading.
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+use bevy_fake_interior::*;
+
+/// Size of the single room atlas.
+const SIZE: u32 = 128;
+/// TV screen on the back wall of the room, in atlas UV.
+const SCREEN_MIN: Vec2 = Vec2::new(0.4, 0.42);
+const SCREEN_MAX: Vec2 = Vec2::new(0.6, 0.56);
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "TV".into(),
+            ..default()
+        }),
+        ..default()
+    }));
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (play, log_asset_events));
+
+    app.run();
+}
+
+/// The streamed emissive atlas.
+#[derive(Resource)]
+struct Screen(Handle<Image>);
+
+/// Atlas image of `SIZE` from a color per texel UV.
+fn atlas_image(usage: RenderAssetUsages, color: impl Fn(Vec2) -> [u8; 4]) -> Image {
+    Image::new(
+        Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        atlas_data(color),
+        TextureFormat::Rgba8UnormSrgb,
+        usage,
+    )
+}
+
+fn atlas_data(color: impl Fn(Vec2) -> [u8; 4]) -> Vec<u8> {
+    (0..SIZE * SIZE)
+        .flat_map(|i| color((UVec2::new(i % SIZE, i / SIZE).as_vec2() + 0.5) / SIZE as f32))
+        .collect()
+}
+
+/// Color of the TV screen at `uv` and `time`: scrolling color bars, black off screen.
+fn screen_color(uv: Vec2, time: f32) -> [u8; 4] {
+    if uv.cmplt(SCREEN_MIN).any() || uv.cmpgt(SCREEN_MAX).any() {
+        return [0, 0, 0, 255];
+    }
+    let x = (uv.x - SCREEN_MIN.x) / (SCREEN_MAX.x - SCREEN_MIN.x);
+    let hue = (x + time * 0.3).fract() * 360.0;
+    let [r, g, b, _] = Color::hsl(hue, 0.8, 0.5).to_srgba().to_u8_array();
+    [r, g, b, 255]
+}
+
+/// set up a wall of rooms with a TV each, a light and a camera
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+) {
+    // plain room, lighter towards the back wall.
+    let room = images.add(atlas_image(RenderAssetUsages::RENDER_WORLD, |uv| {
+        let back = 1.0 - (uv - 0.5).abs().max_element() * 2.0;
+        let shade = (90.0 + 120.0 * back) as u8;
+        [shade, shade, (shade as f32 * 0.8) as u8, 255]
+    }));
+    // only on the GPU, the frames are streamed into it.
+    let screen = images.add(atlas_image(RenderAssetUsages::RENDER_WORLD, |uv| {
+        screen_color(uv, 0.0)
+    }));
+    commands.insert_resource(Screen(screen.clone()));
+
+    let interior = interiors.add(StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(room),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(screen),
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            rooms: Vec2::new(4.0, 3.0),
+            depth: 0.5,
+            emission_threshold: 1.0,
+            emission_intensity: 4.0,
+            ..default()
+        },
+    });
+
+    spawn_fake_interior_wall(
+        &mut commands,
+        &mut meshes,
+        interior,
+        Vec2::new(4.0, 3.0),
+        Transform::IDENTITY,
+    );
+
+    // light
+    commands.spawn((
+        PointLight {
+            intensity: 1500.0,
+            ..default()
+        },
+        Transform::from_xyz(2.0, 3.0, 4.0),
+    ));
+
+    // camera
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(1.0, 0.3, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+/// stream the next frame of the TVs
+fn play(time: Res<Time>, screen: Res<Screen>, mut streamed: ResMut<StreamedImages>) {
+    let t = time.elapsed_secs();
+    streamed.write(&screen.0, atlas_data(|uv| screen_color(uv, t)));
+}
+
+/// log changes of the material and image assets, there are none while playing
+fn log_asset_events(
+    mut materials: EventReader<AssetEvent<StandardFakeInteriorMaterial>>,
+    mut images: EventReader<AssetEvent<Image>>,
+) {
+    for event in materials.read() {
+        info!("material: {event:?}");
+    }
+    for event in images.read() {
+        info!("image: {event:?}");
+    }
+}
@@ -0,0 +1,126 @@
+//! A facade mesh with one UV island per window and `uv_island_rooms`.
+//!
+//! Every window quad of the mesh has the whole `0..1` UV range, like facades exported with a
+//! UV island per window. Without `uv_island_rooms` they would all show the same room.
+
+use bevy::{
+    prelude::*,
+    render::{mesh::Indices, render_asset::RenderAssetUsages, render_resource::PrimitiveTopology},
+};
+
+use bevy_fake_interior::*;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "UV islands".into(),
+            ..default()
+        }),
+        ..default()
+    }));
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin)
+        .add_systems(Startup, setup);
+
+    app.run();
+}
+
+/// Windows `size` large in a `columns` x `rows` grid, `spacing` apart, facing `+Z`. Each
+/// window is its own UV island covering `0..1`.
+fn window_islands_mesh(columns: u32, rows: u32, size: Vec2, spacing: Vec2) -> Mesh {
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+    let origin = -Vec2::new(columns as f32 - 1.0, rows as f32 - 1.0) * spacing / 2.0;
+    for row in 0..rows {
+        for column in 0..columns {
+            let center = origin + Vec2::new(column as f32, row as f32) * spacing;
+            let first = positions.len() as u32;
+            // U along +X and V along -Y, like `fake_interior_wall_mesh`.
+            for (corner, uv) in [
+                (Vec2::new(-0.5, 0.5), [0.0, 0.0]),
+                (Vec2::new(0.5, 0.5), [1.0, 0.0]),
+                (Vec2::new(0.5, -0.5), [1.0, 1.0]),
+                (Vec2::new(-0.5, -0.5), [0.0, 1.0]),
+            ] {
+                positions.push((center + corner * size).extend(0.0).to_array());
+                uvs.push(uv);
+            }
+            indices.extend([first, first + 3, first + 2, first, first + 2, first + 1]);
+        }
+    }
+    let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+    .with_generated_tangents()
+    .expect("the windows have positions, normals and UVs")
+}
+
+/// set up the window islands in front of a plain wall, a light and the camera
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    let interior = interiors.add(StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            depth: 0.5,
+            emission_intensity: 10.0,
+            uv_island_rooms: 1,
+            ..default()
+        },
+    });
+
+    // windows
+    commands.spawn((
+        Mesh3d(meshes.add(window_islands_mesh(
+            5,
+            4,
+            Vec2::new(1.0, 1.2),
+            Vec2::new(1.6, 1.8),
+        ))),
+        MeshMaterial3d(interior),
+        Name::new("Windows"),
+    ));
+
+    // wall around them, just behind.
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::new(Vec3::Z, Vec2::new(4.6, 4.0)))),
+        MeshMaterial3d(materials.add(Color::srgb(0.55, 0.5, 0.45))),
+        Transform::from_xyz(0.0, 0.0, -0.01),
+    ));
+
+    // light
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 2000.0,
+            ..default()
+        },
+        Transform::from_xyz(2.0, 4.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // camera
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(3.0, 1.0, 9.0).looking_at(Vec3::ZERO, Vec3::Y),
+        bevy_panorbit_camera::PanOrbitCamera::default(),
+    ));
+}
@@ -0,0 +1,98 @@
+//! Spawn a row of fake interior walls with `spawn_fake_interior_wall`.
+
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+};
+
+use bevy_fake_interior::*;
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins((
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Wall helper".into(),
+                    ..default()
+                }),
+                ..default()
+            })
+            .set(AssetPlugin {
+                mode: AssetMode::Processed,
+                ..default()
+            }),
+        LogDiagnosticsPlugin::default(),
+        FrameTimeDiagnosticsPlugin,
+    ));
+
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
+        .add_plugins(bevy_panorbit_camera::PanOrbitCameraPlugin)
+        .add_systems(Startup, setup);
+
+    app.run();
+}
+
+/// set up a simple 3D scene
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    let interior = interiors.add(StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
+            reflectance: 1.0,
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(3.0, 6.0),
+            depth: 0.5,
+            room_seed: 1.2,
+            emission_intensity: 10.0,
+            ..default()
+        },
+    });
+
+    // a row of walls, each with its own mesh, material and transform.
+    for i in 0..3 {
+        let wall = spawn_fake_interior_wall(
+            &mut commands,
+            &mut meshes,
+            interior.clone(),
+            Vec2::new(1.0, 2.0),
+            Transform::from_xyz(i as f32 * 1.1 - 1.1, 0.0, 0.0),
+        );
+        commands.entity(wall).insert(Name::new(format!("Wall {i}")));
+    }
+
+    // light
+    commands.spawn((
+        PointLight {
+            intensity: 1500.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 8.0, 4.0),
+    ));
+
+    // camera
+    let mut cam = commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    cam.insert(bevy_panorbit_camera::PanOrbitCamera {
+        focus: Vec3::ZERO,
+        radius: Some(5.0),
+        yaw: Some(0.00),
+        pitch: Some(0.0),
+        ..default()
+    });
+    cam.insert(Name::new("Camera"));
+}
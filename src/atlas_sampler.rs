@@ -0,0 +1,107 @@
+//! Address modes of the room atlas samplers.
+
+use bevy::{
+    image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor},
+    prelude::*,
+    render::render_resource::AddressMode,
+};
+
+use crate::StandardFakeInteriorMaterial;
+
+/// How the room atlas is sampled past its edges, see
+/// [`FakeInteriorMaterial::atlas_address_mode_u`](crate::FakeInteriorMaterial::atlas_address_mode_u).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Default, Debug, PartialEq)]
+pub enum AtlasAddressMode {
+    /// Filter with the edge texels, the rooms on the atlas edges don't bleed into the rooms
+    /// on the opposite edge.
+    #[default]
+    ClampToEdge,
+    /// Filter with the texels of the opposite edge.
+    Repeat,
+    /// Filter with the texels of the same edge, mirrored.
+    MirrorRepeat,
+}
+
+impl From<AtlasAddressMode> for ImageAddressMode {
+    fn from(mode: AtlasAddressMode) -> Self {
+        match mode {
+            AtlasAddressMode::ClampToEdge => Self::ClampToEdge,
+            AtlasAddressMode::Repeat => Self::Repeat,
+            AtlasAddressMode::MirrorRepeat => Self::MirrorRepeat,
+        }
+    }
+}
+
+/// The app's `ImagePlugin::default_sampler`, the sampler of images with
+/// `ImageSampler::Default`.
+#[derive(Resource)]
+struct DefaultAtlasSampler(ImageSamplerDescriptor);
+
+pub(crate) fn init_atlas_samplers(app: &mut App) {
+    let default_sampler = app
+        .get_added_plugins::<ImagePlugin>()
+        .first()
+        .map(|plugin| plugin.default_sampler.clone())
+        .unwrap_or_default();
+    app.insert_resource(DefaultAtlasSampler(default_sampler))
+        .add_systems(Update, update_atlas_samplers);
+}
+
+/// Set the address modes of the atlas textures of the fake interior materials.
+///
+/// The sampler is part of the `Image`, so a texture shared by materials with different
+/// modes gets the mode of one of them, and other users of the image sample it with that
+/// mode too. An image with the `ImagePlugin`'s default sampler gets a copy of it with only
+/// the address modes changed, keeping e.g. `ImagePlugin::default_nearest()`, and only if the
+/// default's modes differ.
+fn update_atlas_samplers(
+    mut material_events: EventReader<AssetEvent<StandardFakeInteriorMaterial>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    materials: Res<Assets<StandardFakeInteriorMaterial>>,
+    default_sampler: Res<DefaultAtlasSampler>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    // our own sampler changes are `Modified` events and don't trigger another update.
+    let materials_changed = material_events.read().count() > 0;
+    let images_loaded = image_events
+        .read()
+        .filter(|event| matches!(event, AssetEvent::LoadedWithDependencies { .. }))
+        .count()
+        > 0;
+    if !materials_changed && !images_loaded {
+        return;
+    }
+
+    for (_, material) in materials.iter() {
+        let mode_u = ImageAddressMode::from(material.extension.atlas_address_mode_u);
+        let mode_v = ImageAddressMode::from(material.extension.atlas_address_mode_v);
+        let atlases = [
+            &material.base.base_color_texture,
+            &material.base.emissive_texture,
+        ];
+        for atlas in atlases.into_iter().flatten() {
+            let Some(image) = images.get(atlas) else {
+                continue;
+            };
+            let current = match &image.sampler {
+                ImageSampler::Default => &default_sampler.0,
+                ImageSampler::Descriptor(descriptor) => descriptor,
+            };
+            if AddressMode::from(current.address_mode_u) == AddressMode::from(mode_u)
+                && AddressMode::from(current.address_mode_v) == AddressMode::from(mode_v)
+            {
+                continue;
+            }
+            let Some(image) = images.get_mut(atlas) else {
+                continue;
+            };
+            if let ImageSampler::Default = image.sampler {
+                image.sampler = ImageSampler::Descriptor(default_sampler.0.clone());
+            }
+            let descriptor = image.sampler.get_or_init_descriptor();
+            descriptor.address_mode_u = mode_u;
+            descriptor.address_mode_v = mode_v;
+        }
+    }
+}
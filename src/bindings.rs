@@ -0,0 +1,44 @@
+//! Bindings of the fake interior material extension.
+//!
+//! The extension's bindings in the material bind group (`@group(2)`) are all in
+//! [`FAKE_INTERIOR_BINDINGS`], leave that range free when composing the material with
+//! another extension. New bindings take the next free index of the range. The
+//! `AsBindGroup` derive of [`FakeInteriorMaterial`](crate::FakeInteriorMaterial) needs
+//! literal indices, keep them in sync. `tests/shader_validation.rs` checks the bindings of
+//! `fake_interior.wgsl` against these constants.
+
+use std::ops::Range;
+
+/// Binding indices reserved for the fake interior extension, `StandardMaterial` uses the
+/// indices below it.
+pub const FAKE_INTERIOR_BINDINGS: Range<u32> = 100..120;
+
+/// The `FakeInteriorMaterialUniform`.
+pub const FAKE_INTERIOR_UNIFORM_BINDING: u32 = 100;
+/// Per-instance parameter table, only with the `instance_params` feature.
+pub const FAKE_INTERIOR_INSTANCES_BINDING: u32 = 101;
+/// `occupant_texture`. This and the following optional textures are only bound with the
+/// `extra_textures` feature.
+pub const FAKE_INTERIOR_OCCUPANT_TEXTURE_BINDING: u32 = 102;
+/// Sampler of `occupant_texture`.
+pub const FAKE_INTERIOR_OCCUPANT_SAMPLER_BINDING: u32 = 103;
+/// `light_palette_texture`, read without a sampler.
+pub const FAKE_INTERIOR_LIGHT_PALETTE_BINDING: u32 = 104;
+/// `mullion_mask_texture`.
+pub const FAKE_INTERIOR_MULLION_MASK_TEXTURE_BINDING: u32 = 105;
+/// Sampler of `mullion_mask_texture`.
+pub const FAKE_INTERIOR_MULLION_MASK_SAMPLER_BINDING: u32 = 106;
+/// `room_images`, a 2d array texture.
+pub const FAKE_INTERIOR_ROOM_IMAGES_BINDING: u32 = 107;
+/// Sampler of `room_images`.
+pub const FAKE_INTERIOR_ROOM_IMAGES_SAMPLER_BINDING: u32 = 108;
+/// `room_index_map`, read without a sampler.
+pub const FAKE_INTERIOR_ROOM_INDEX_MAP_BINDING: u32 = 109;
+/// `planar_reflection_texture`.
+pub const FAKE_INTERIOR_PLANAR_REFLECTION_TEXTURE_BINDING: u32 = 110;
+/// Sampler of `planar_reflection_texture`.
+pub const FAKE_INTERIOR_PLANAR_REFLECTION_SAMPLER_BINDING: u32 = 111;
+/// `emissive_layers`, a 2d array texture.
+pub const FAKE_INTERIOR_EMISSIVE_LAYERS_BINDING: u32 = 112;
+/// Sampler of `emissive_layers`.
+pub const FAKE_INTERIOR_EMISSIVE_LAYERS_SAMPLER_BINDING: u32 = 113;
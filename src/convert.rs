@@ -0,0 +1,69 @@
+//! Turn plain `StandardMaterial` walls into fake interior walls.
+
+use bevy::prelude::*;
+
+use crate::{FakeInteriorMaterial, StandardFakeInteriorMaterial};
+
+/// Replace the entity's `MeshMaterial3d<StandardMaterial>` by a fake interior material built
+/// from the `StandardMaterial` in `.0`, keeping its textures and other settings, and the
+/// extension `.1`.
+///
+/// The component is removed once the material is converted, waiting for the `StandardMaterial`
+/// while it's loading. It's dropped with a warning if the material failed to load. Each
+/// converted entity gets its own material asset, for many walls sharing a material convert
+/// one and give the others its `MeshMaterial3d`. Like any fake interior, the mesh needs
+/// tangents.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fake_interior::*;
+/// fn retrofit(
+///     mut commands: Commands,
+///     walls: Query<(Entity, &MeshMaterial3d<StandardMaterial>), With<Name>>,
+/// ) {
+///     for (entity, material) in &walls {
+///         commands.entity(entity).insert(FakeInteriorFrom(
+///             material.0.clone(),
+///             FakeInteriorMaterial {
+///                 rooms: Vec2::new(4.0, 3.0),
+///                 ..default()
+///             },
+///         ));
+///     }
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct FakeInteriorFrom(pub Handle<StandardMaterial>, pub FakeInteriorMaterial);
+
+pub(crate) fn init_convert(app: &mut App) {
+    app.add_systems(Update, convert_materials);
+}
+
+fn convert_materials(
+    mut commands: Commands,
+    entities: Query<(Entity, &FakeInteriorFrom)>,
+    standard_materials: Res<Assets<StandardMaterial>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, FakeInteriorFrom(source, extension)) in &entities {
+        let Some(base) = standard_materials.get(source) else {
+            if asset_server
+                .get_load_state(source)
+                .is_some_and(|state| state.is_failed())
+            {
+                warn!("FakeInteriorFrom: the material of {entity} failed to load");
+                commands.entity(entity).remove::<FakeInteriorFrom>();
+            }
+            continue;
+        };
+        let interior = interiors.add(StandardFakeInteriorMaterial {
+            base: base.clone(),
+            extension: extension.clone(),
+        });
+        commands
+            .entity(entity)
+            .remove::<(FakeInteriorFrom, MeshMaterial3d<StandardMaterial>)>()
+            .insert(MeshMaterial3d(interior));
+    }
+}
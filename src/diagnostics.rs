@@ -0,0 +1,76 @@
+//! Diagnostics of the fake interior walls for profiling, with the `diagnostics` feature.
+//!
+//! Bevy records GPU time per render pass, not per material: the fake interiors are drawn in
+//! the same prepass and main passes as everything else, so their own cost can't be measured
+//! directly. Besides the number of visible walls, [`FakeInteriorDiagnostics::SCENE_PASSES_GPU`]
+//! is the GPU time of those passes for the whole scene. Compare it with the walls hidden, or
+//! switched to [`QualityTier::Low`], to get their share.
+//!
+//! [`QualityTier::Low`]: crate::QualityTier::Low
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, RegisterDiagnostic},
+    prelude::*,
+    render::{diagnostic::RenderDiagnosticsPlugin, view::VisibilitySystems},
+};
+
+use crate::StandardFakeInteriorMaterial;
+
+/// Paths of the fake interior diagnostics in the [`DiagnosticsStore`], added by the
+/// [`FakeInteriorMaterialPlugin`](crate::FakeInteriorMaterialPlugin) with the `diagnostics`
+/// feature. It also adds Bevy's [`RenderDiagnosticsPlugin`] for the pass timings, add that
+/// before it if the app adds it too.
+///
+/// GPU timings need timestamp queries, only on Vulkan and DX12, and arrive a few frames late.
+pub struct FakeInteriorDiagnostics;
+
+impl FakeInteriorDiagnostics {
+    /// Entities with a fake interior material visible in any view.
+    pub const VISIBLE_WALLS: DiagnosticPath =
+        DiagnosticPath::const_new("fake_interior/visible_walls");
+    /// GPU milliseconds of the whole scene's 3D prepass and main passes, everything drawn in
+    /// them and not only the fake interiors.
+    pub const SCENE_PASSES_GPU: DiagnosticPath =
+        DiagnosticPath::const_new("fake_interior/scene_passes_gpu");
+
+    /// The render pass timings summed up in [`SCENE_PASSES_GPU`](Self::SCENE_PASSES_GPU).
+    pub const PASSES: [DiagnosticPath; 3] = [
+        DiagnosticPath::const_new("render/prepass/elapsed_gpu"),
+        DiagnosticPath::const_new("render/main_opaque_pass_3d/elapsed_gpu"),
+        DiagnosticPath::const_new("render/main_transparent_pass_3d/elapsed_gpu"),
+    ];
+}
+
+pub(crate) fn init_diagnostics(app: &mut App) {
+    if !app.is_plugin_added::<RenderDiagnosticsPlugin>() {
+        app.add_plugins(RenderDiagnosticsPlugin);
+    }
+    app.register_diagnostic(Diagnostic::new(FakeInteriorDiagnostics::VISIBLE_WALLS))
+        .register_diagnostic(
+            Diagnostic::new(FakeInteriorDiagnostics::SCENE_PASSES_GPU).with_suffix("ms"),
+        )
+        .add_systems(
+            PostUpdate,
+            measure_walls.after(VisibilitySystems::CheckVisibility),
+        );
+}
+
+fn measure_walls(
+    walls: Query<&ViewVisibility, With<MeshMaterial3d<StandardFakeInteriorMaterial>>>,
+    store: Res<DiagnosticsStore>,
+    mut diagnostics: Diagnostics,
+) {
+    diagnostics.add_measurement(&FakeInteriorDiagnostics::VISIBLE_WALLS, || {
+        walls.iter().filter(|visibility| visibility.get()).count() as f64
+    });
+    // the render world sends the pass timings once they're read back.
+    let passes: Vec<f64> = FakeInteriorDiagnostics::PASSES
+        .iter()
+        .filter_map(|path| store.get(path)?.value())
+        .collect();
+    if !passes.is_empty() {
+        diagnostics.add_measurement(&FakeInteriorDiagnostics::SCENE_PASSES_GPU, || {
+            passes.iter().sum()
+        });
+    }
+}
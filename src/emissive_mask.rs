@@ -0,0 +1,89 @@
+//! Generate the lit mask of a room atlas from its base color.
+
+use bevy::{
+    image::TextureAccessError,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use serde::Deserialize;
+
+/// Rule turning a base color room atlas into its lit mask (the `emissive_texture`), so only
+/// one atlas has to be authored: the texels brighter than `threshold` glow with their own
+/// color, the others stay dark.
+///
+/// Set `emissive_from_base` in a `.fakeinterior.ron` file to generate it when the material is
+/// loaded, see [`FakeInteriorDescription`](crate::FakeInteriorDescription), or call
+/// [`generate`](Self::generate).
+///
+/// ```
+/// # use bevy::{prelude::*, render::{render_asset::RenderAssetUsages, render_resource::*}};
+/// # use bevy_fake_interior::*;
+/// // a dark wall texel and a bright window texel.
+/// let atlas = Image::new(
+///     Extent3d { width: 2, height: 1, depth_or_array_layers: 1 },
+///     TextureDimension::D2,
+///     vec![20, 20, 20, 255, 250, 240, 200, 255],
+///     TextureFormat::Rgba8UnormSrgb,
+///     RenderAssetUsages::default(),
+/// );
+/// let mask = FakeInteriorEmissiveMask::default().generate(&atlas).unwrap();
+/// assert_eq!(mask.get_color_at(0, 0).unwrap(), Color::srgb_u8(0, 0, 0));
+/// assert_eq!(mask.get_color_at(1, 0).unwrap(), Color::srgb_u8(250, 240, 200));
+/// ```
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FakeInteriorEmissiveMask {
+    /// Linear luminance above which a texel is lit.
+    pub threshold: f32,
+    /// Width of the ramp from unlit to lit around `threshold`, `0.0` for a hard edge.
+    pub softness: f32,
+}
+
+impl Default for FakeInteriorEmissiveMask {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            softness: 0.1,
+        }
+    }
+}
+
+impl FakeInteriorEmissiveMask {
+    /// How much a texel of linear luminance `luminance` is lit, from `0.0` to `1.0`.
+    pub fn lit(&self, luminance: f32) -> f32 {
+        if self.softness <= 0.0 {
+            return if luminance > self.threshold { 1.0 } else { 0.0 };
+        }
+        ((luminance - self.threshold) / self.softness + 0.5).clamp(0.0, 1.0)
+    }
+
+    /// The lit mask of `atlas`, an sRGB image of the same size and sampler. Only the first
+    /// layer of array atlases is read, and compressed formats aren't supported.
+    pub fn generate(&self, atlas: &Image) -> Result<Image, TextureAccessError> {
+        let size = atlas.size();
+        let mut mask = Image::new_fill(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        mask.sampler = atlas.sampler.clone();
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let color = atlas.get_color_at(x, y)?.to_linear();
+                let lit = self.lit(color.luminance());
+                let glow = LinearRgba::rgb(color.red * lit, color.green * lit, color.blue * lit);
+                mask.set_color_at(x, y, glow.into())?;
+            }
+        }
+        Ok(mask)
+    }
+}
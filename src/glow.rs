@@ -0,0 +1,69 @@
+//! Selective bloom: render the glow of the interiors with a separate camera.
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::CameraOutputMode,
+        render_resource::{BlendComponent, BlendFactor, BlendOperation, BlendState},
+        view::RenderLayers,
+    },
+};
+
+use crate::StandardFakeInteriorMaterial;
+
+/// Adds the glow camera's output to what the cameras before it rendered.
+const ADDITIVE_BLEND: BlendState = BlendState {
+    color: BlendComponent {
+        src_factor: BlendFactor::One,
+        dst_factor: BlendFactor::One,
+        operation: BlendOperation::Add,
+    },
+    alpha: BlendComponent {
+        src_factor: BlendFactor::Zero,
+        dst_factor: BlendFactor::One,
+        operation: BlendOperation::Add,
+    },
+};
+
+/// Split `material` into the material of the wall seen by the main camera, which has no
+/// glow, and a night mode (`night_only`) material for a copy of the wall on the glow layer.
+///
+/// The glow material keeps the room lights, palette and `interior_ambient`. The far
+/// windows stay in the main material and don't bloom.
+pub fn split_fake_interior_glow(
+    material: &StandardFakeInteriorMaterial,
+) -> (StandardFakeInteriorMaterial, StandardFakeInteriorMaterial) {
+    let mut main = material.clone();
+    main.base.emissive = LinearRgba::BLACK;
+    main.extension.interior_ambient.w = 0.0;
+
+    let mut glow = material.clone();
+    glow.extension.night_only = 1;
+    (main, glow)
+}
+
+/// Camera for selective bloom, rendering only `layers` (the glow copies of the walls from
+/// [`split_fake_interior_glow`]) on black and adding its output to the cameras rendered
+/// before it. Add a `Bloom` to it and make it a child of the main camera so it follows it.
+///
+/// It's an HDR camera without MSAA, the main camera must not be both of these: cameras
+/// sharing the same render target, `hdr` and `Msaa` share their textures and the bloom would
+/// apply to the whole scene. Objects only on the main camera's layers don't hide the glow,
+/// put occluders in front of the walls on the glow layer too, with a black unlit material.
+pub fn fake_interior_glow_camera(order: isize, layers: RenderLayers) -> impl Bundle {
+    (
+        Camera3d::default(),
+        Camera {
+            order,
+            hdr: true,
+            clear_color: ClearColorConfig::Custom(Color::BLACK),
+            output_mode: CameraOutputMode::Write {
+                blend_state: Some(ADDITIVE_BLEND),
+                clear_color: ClearColorConfig::None,
+            },
+            ..default()
+        },
+        Msaa::Off,
+        layers,
+    )
+}
@@ -0,0 +1,349 @@
+//! Per-instance parameters for shared fake interior materials.
+//!
+//! One material asset can render many varied buildings by looking up per-entity overrides
+//! in a storage buffer. Storage buffers aren't available on WebGL2, so this is only built
+//! with the `instance_params` feature.
+//!
+//! The shader has no stable per-entity index, so the table is keyed by the entity's world
+//! translation: a small open-addressing hash table built on the CPU and probed in
+//! `fake_interior.wgsl`. The shader can't tell apart entities whose translations round to the
+//! same 1/64 unit, only the first of them (by [`Entity`] order) keeps its overrides and the
+//! others are dropped with a warning.
+
+use bevy::{
+    prelude::*,
+    render::{render_resource::ShaderType, storage::ShaderStorageBuffer},
+    utils::{HashMap, HashSet},
+};
+
+use crate::StandardFakeInteriorMaterial;
+
+/// Storage buffer with the per-instance table, bound by every fake interior material.
+pub const FAKE_INTERIOR_INSTANCES_HANDLE: Handle<ShaderStorageBuffer> =
+    Handle::weak_from_u128(0x6f1c_3a9e_52d4_4b8f_9e07_1d2c_84a5_b316);
+
+/// Keep in sync with `fake_interior.wgsl`.
+const INSTANCE_MAX_PROBES: usize = 8;
+const INSTANCE_POSITION_SCALE: f32 = 64.0;
+/// How many times the table may double past twice the entry count to fit every entry.
+const INSTANCE_MAX_GROWTH: u32 = 4;
+const INSTANCE_FLAG_DEPTH: u32 = 1;
+const INSTANCE_FLAG_ROOM_SEED: u32 = 2;
+const INSTANCE_FLAG_UNPOWERED: u32 = 4;
+
+/// Overrides of the shared material parameters for one entity.
+#[derive(Clone, Debug, Reflect)]
+#[reflect(Default, Debug)]
+pub struct FakeInteriorInstance {
+    pub depth: Option<f32>,
+    pub room_seed: Option<f32>,
+    /// Multiplies the interior color and glow.
+    pub tint: Vec4,
+    /// `false` turns the room lights off.
+    pub powered: bool,
+}
+
+impl Default for FakeInteriorInstance {
+    fn default() -> Self {
+        Self {
+            depth: None,
+            room_seed: None,
+            tint: Vec4::ONE,
+            powered: true,
+        }
+    }
+}
+
+/// Overrides of the shared material parameters for the entity it's on, kept in
+/// [`FakeInteriorInstances`] while the component exists. `None` keeps the material's value.
+///
+/// Walls with overrides keep sharing their material and pipeline. Adding, changing or
+/// removing an override (or moving its entity) rebuilds the whole instance table, and when it
+/// changed uploads it and rebinds the fake interior materials using it, so it's cheap for
+/// static scenery but not for per-frame animation. Every fragment of every fake interior
+/// probes the table, up to 8 slots.
+///
+/// The shader finds an entity's entry by its world translation, Bevy 0.15 gives it no
+/// per-entity index. Entities on the same translation (to 1/64 unit), e.g. several wall meshes
+/// parented to one origin with identity transforms, can't have different overrides: the first
+/// of them (in [`Entity`] order) keeps its entry and all of them render with it, the others'
+/// parameters are dropped with a warning. Give each wall mesh its own origin instead.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct FakeInteriorOverride {
+    pub depth: Option<f32>,
+    pub room_seed: Option<f32>,
+    /// Multiplies the interior color and glow.
+    pub tint: Option<Vec4>,
+}
+
+/// Whether the room lights of the entity it's on are on, e.g. for a block losing power.
+///
+/// `false` turns off the glow of its lit rooms without touching the shared material, the
+/// interior ambient floor and far windows stay. Like [`FakeInteriorOverride`] it's kept in
+/// [`FakeInteriorInstances`] while the component exists, with the same costs and the same
+/// restriction to one entry per world translation.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_fake_interior::*;
+/// fn blackout(
+///     mut commands: Commands,
+///     walls: Query<Entity, With<MeshMaterial3d<StandardFakeInteriorMaterial>>>,
+/// ) {
+///     for entity in &walls {
+///         commands.entity(entity).insert(FakeInteriorPower(false));
+///     }
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct FakeInteriorPower(pub bool);
+
+impl Default for FakeInteriorPower {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+impl From<&FakeInteriorOverride> for FakeInteriorInstance {
+    fn from(params: &FakeInteriorOverride) -> Self {
+        Self {
+            depth: params.depth,
+            room_seed: params.room_seed,
+            tint: params.tint.unwrap_or(Vec4::ONE),
+            powered: true,
+        }
+    }
+}
+
+/// Per-instance parameters uploaded to [`FAKE_INTERIOR_INSTANCES_HANDLE`].
+///
+/// Entities are keyed by their [`GlobalTransform`] translation when the table is uploaded, see
+/// [`FakeInteriorOverride`] for entities sharing one.
+#[derive(Resource, Default, Debug)]
+pub struct FakeInteriorInstances {
+    instances: HashMap<Entity, FakeInteriorInstance>,
+}
+
+impl FakeInteriorInstances {
+    pub fn insert(&mut self, entity: Entity, instance: FakeInteriorInstance) {
+        self.instances.insert(entity, instance);
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<FakeInteriorInstance> {
+        self.instances.remove(&entity)
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&FakeInteriorInstance> {
+        self.instances.get(&entity)
+    }
+
+    pub fn clear(&mut self) {
+        self.instances.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, Default, ShaderType)]
+struct FakeInteriorInstanceEntry {
+    /// `w = 1.0` marks a used slot.
+    position: Vec4,
+    tint: Vec4,
+    depth: f32,
+    room_seed: f32,
+    flags: u32,
+    padding: u32,
+}
+
+impl FakeInteriorInstanceEntry {
+    fn new(position: Vec3, instance: &FakeInteriorInstance) -> Self {
+        let mut flags = 0;
+        if instance.depth.is_some() {
+            flags |= INSTANCE_FLAG_DEPTH;
+        }
+        if instance.room_seed.is_some() {
+            flags |= INSTANCE_FLAG_ROOM_SEED;
+        }
+        if !instance.powered {
+            flags |= INSTANCE_FLAG_UNPOWERED;
+        }
+        Self {
+            position: position.extend(1.0),
+            tint: instance.tint,
+            depth: instance.depth.unwrap_or_default(),
+            room_seed: instance.room_seed.unwrap_or_default(),
+            flags,
+            padding: 0,
+        }
+    }
+}
+
+fn instance_key(position: Vec3) -> IVec3 {
+    (position * INSTANCE_POSITION_SCALE + 0.5)
+        .floor()
+        .as_ivec3()
+}
+
+fn instance_hash(q: IVec3) -> u32 {
+    (q.x as u32).wrapping_mul(73856093)
+        ^ (q.y as u32).wrapping_mul(19349663)
+        ^ (q.z as u32).wrapping_mul(83492791)
+}
+
+/// Build the hash table, growing it until every entry is within `INSTANCE_MAX_PROBES` of its slot.
+///
+/// Entries with the key of an earlier one are dropped, and so are the entries still not
+/// fitting after `INSTANCE_MAX_GROWTH` doublings, both with a warning.
+fn build_table(entries: &[FakeInteriorInstanceEntry]) -> Vec<FakeInteriorInstanceEntry> {
+    let mut keys = HashSet::new();
+    let unique: Vec<_> = entries
+        .iter()
+        .filter(|entry| keys.insert(instance_key(entry.position.truncate())))
+        .collect();
+    let duplicates = entries.len() - unique.len();
+    if duplicates > 0 {
+        warn!(
+            "{} fake interior instances share the translation of an earlier one, their overrides are dropped",
+            duplicates
+        );
+    }
+    let min_capacity = (unique.len() * 2).next_power_of_two().max(1);
+    let mut capacity = min_capacity;
+    loop {
+        let mut table = vec![FakeInteriorInstanceEntry::default(); capacity];
+        let mut dropped = 0;
+        for entry in &unique {
+            let key = instance_key(entry.position.truncate());
+            let mut slot = instance_hash(key) as usize & (capacity - 1);
+            let mut placed = false;
+            for _ in 0..INSTANCE_MAX_PROBES {
+                if table[slot].position.w == 0.0 {
+                    table[slot] = **entry;
+                    placed = true;
+                    break;
+                }
+                slot = (slot + 1) & (capacity - 1);
+            }
+            if !placed {
+                dropped += 1;
+            }
+        }
+        if dropped == 0 {
+            return table;
+        }
+        if capacity >= min_capacity << INSTANCE_MAX_GROWTH {
+            warn!(
+                "{} fake interior instances collide in the instance table and are dropped",
+                dropped
+            );
+            return table;
+        }
+        capacity *= 2;
+    }
+}
+
+pub(crate) fn init_instance_buffer(app: &mut App) {
+    assert!(
+        app.is_plugin_added::<bevy::render::RenderPlugin>(),
+        "FakeInteriorMaterialPlugin with `instance_params` must be added after DefaultPlugins (or RenderPlugin)"
+    );
+    app.init_resource::<FakeInteriorInstances>()
+        .register_type::<FakeInteriorInstance>()
+        .register_type::<FakeInteriorOverride>()
+        .register_type::<FakeInteriorPower>()
+        .add_systems(
+            PostUpdate,
+            (sync_overrides, update_instance_buffer)
+                .chain()
+                .after(TransformSystem::TransformPropagate),
+        );
+    let table = build_table(&[]);
+    app.world_mut()
+        .resource_mut::<Assets<ShaderStorageBuffer>>()
+        .insert(
+            FAKE_INTERIOR_INSTANCES_HANDLE.id(),
+            ShaderStorageBuffer::from(table),
+        );
+}
+
+type InstanceComponentsChanged = Or<(Changed<FakeInteriorOverride>, Changed<FakeInteriorPower>)>;
+
+/// Mirror the [`FakeInteriorOverride`] and [`FakeInteriorPower`] components into
+/// [`FakeInteriorInstances`].
+fn sync_overrides(
+    changed: Query<Entity, InstanceComponentsChanged>,
+    components: Query<(Option<&FakeInteriorOverride>, Option<&FakeInteriorPower>)>,
+    mut removed_overrides: RemovedComponents<FakeInteriorOverride>,
+    mut removed_power: RemovedComponents<FakeInteriorPower>,
+    mut instances: ResMut<FakeInteriorInstances>,
+) {
+    let removed: Vec<_> = removed_overrides
+        .read()
+        .chain(removed_power.read())
+        .collect();
+    for entity in removed.into_iter().chain(&changed) {
+        match components.get(entity) {
+            Ok((None, None)) | Err(_) => {
+                instances.remove(entity);
+            }
+            Ok((params, power)) => {
+                let mut instance: FakeInteriorInstance = params.map(Into::into).unwrap_or_default();
+                instance.powered = power.is_none_or(|power| power.0);
+                instances.insert(entity, instance);
+            }
+        }
+    }
+}
+
+fn update_instance_buffer(
+    instances: Res<FakeInteriorInstances>,
+    transforms: Query<&GlobalTransform>,
+    moved: Query<Entity, Changed<GlobalTransform>>,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+    mut materials: ResMut<Assets<StandardFakeInteriorMaterial>>,
+) {
+    if !instances.is_changed() && !moved.iter().any(|e| instances.instances.contains_key(&e)) {
+        return;
+    }
+    // in entity order, so the same entity wins a shared translation on every rebuild.
+    let mut sorted: Vec<_> = instances.instances.iter().collect();
+    sorted.sort_unstable_by_key(|(entity, _)| **entity);
+    let entries: Vec<_> = sorted
+        .into_iter()
+        .filter_map(|(entity, instance)| {
+            let transform = transforms.get(*entity).ok()?;
+            Some(FakeInteriorInstanceEntry::new(
+                transform.translation(),
+                instance,
+            ))
+        })
+        .collect();
+    let table = ShaderStorageBuffer::from(build_table(&entries));
+    if buffers
+        .get(&FAKE_INTERIOR_INSTANCES_HANDLE)
+        .is_some_and(|buffer| buffer.data == table.data)
+    {
+        return;
+    }
+    buffers.insert(FAKE_INTERIOR_INSTANCES_HANDLE.id(), table);
+    // The changed table is uploaded to a new GPU buffer, the bind groups of the materials
+    // binding it hold the old one: mark those modified so they get rebuilt.
+    let binding: Vec<_> = materials
+        .iter()
+        .filter(|(_, material)| {
+            material.extension.instances.id() == FAKE_INTERIOR_INSTANCES_HANDLE.id()
+        })
+        .map(|(id, _)| id)
+        .collect();
+    for id in binding {
+        materials.get_mut(id);
+    }
+}
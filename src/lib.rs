@@ -1,24 +1,337 @@
 //! A shader and a material that uses it.
 
 use bevy::{
-    pbr::{ExtendedMaterial, MaterialExtension},
+    pbr::{
+        ExtendedMaterial, MaterialExtension, MaterialExtensionKey, MaterialExtensionPipeline,
+        PBR_PREPASS_SHADER_HANDLE,
+    },
     prelude::*,
     reflect::Reflect,
-    render::{render_asset::*, render_resource::*, texture::GpuImage},
+    render::{mesh::MeshVertexBufferLayoutRef, render_resource::*},
 };
 
+#[cfg(feature = "instance_params")]
+use bevy::render::storage::ShaderStorageBuffer;
+
+#[cfg(feature = "instance_params")]
+mod instance;
+#[cfg(feature = "instance_params")]
+pub use instance::*;
+
+mod atlas_sampler;
+pub use atlas_sampler::*;
+
+mod bindings;
+pub use bindings::*;
+
+mod convert;
+pub use convert::*;
+
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::*;
+
+mod emissive_mask;
+pub use emissive_mask::*;
+
+mod glow;
+pub use glow::*;
+
+mod loader;
+pub use loader::*;
+
+mod lod;
+pub use lod::*;
+
+mod params;
+pub use params::*;
+
+mod placeholder;
+pub use placeholder::*;
+
+mod quality;
+pub use quality::*;
+
+mod ready;
+pub use ready::*;
+
+mod room_images;
+pub use room_images::*;
+
+mod streamed_image;
+pub use streamed_image::*;
+
+mod trace;
+
+mod wall;
+pub use wall::*;
+
 pub type StandardFakeInteriorMaterial = ExtendedMaterial<StandardMaterial, FakeInteriorMaterial>;
 
+/// Fake interior extension of the [`StandardMaterial`], its bindings are in
+/// [`FAKE_INTERIOR_BINDINGS`].
 #[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+// binding indices are the `FAKE_INTERIOR_*_BINDING` constants, the derive needs literals.
 #[uniform(100, FakeInteriorMaterialUniform)]
+#[bind_group_data(FakeInteriorMaterialKey)]
 #[reflect(Default, Debug)]
 pub struct FakeInteriorMaterial {
     pub atlas_rooms: Vec2,
+    /// Number of rooms across the surface, at most [`MAX_ROOMS`](Self::MAX_ROOMS) along each
+    /// axis. Larger counts are clamped with a warning.
     pub rooms: Vec2,
+    /// Room depth, `0.0` draws each room flat in its window without parallax.
     pub depth: f32,
+    /// Seed of the room layout: the atlas room, depth and details (far windows, occupants, solid
+    /// cells) of each room cell.
     pub room_seed: f32,
+    /// Seed of the lit rooms (with `emission_threshold`) and their `light_palette` colors.
     pub emission_seed: f32,
     pub emission_threshold: f32,
+    /// Room depth in world units, used instead of `depth` when `use_depth_world` is non-zero.
+    pub depth_world: f32,
+    /// Select `depth_world` over the normalized `depth`.
+    pub use_depth_world: u32,
+    /// UV range covered by the surface, e.g. `Vec2::splat(3.0)` for 3x tiled UVs.
+    /// `rooms` is the number of rooms across this whole range.
+    ///
+    /// It can't be derived in the shader: the UV derivatives only tell how fast the UVs change
+    /// at a fragment, not how far the mesh's UVs reach: a fragment of a 3x tiled wall looks the
+    /// same as one of a wall a third of its size with `0..1` UVs. The derivatives do give the
+    /// world size of the room cells, which keeps their aspect and the parallax depth right
+    /// whatever the tiling.
+    pub uv_tiling: Vec2,
+    /// Night mode: skip the base-color interior and only render the glow of lit rooms
+    /// on a dark wall (forward rendering only).
+    pub night_only: u32,
+    /// Interior box in room cell space. The window cell spans `-1..1` on x/y and sits at
+    /// `box_min.z`; `z = 1` is the back wall at `depth`. Must contain the window cell.
+    pub box_min: Vec3,
+    pub box_max: Vec3,
+    /// Color of the reveal line drawn along each room cell edge, `w = 0` disables it.
+    pub reveal_color: Vec4,
+    /// Width of the reveal line in room cell UV units.
+    pub reveal_width: f32,
+    /// Per-entity variation of the room grid as a fraction of `rooms`, seeded by the
+    /// entity's world position. The jittered grid is always rounded to whole rooms.
+    pub rooms_jitter: Vec2,
+    /// Separation of the red and blue interior samples along the view direction, in room
+    /// cell UV units. `0.0` disables it.
+    pub chromatic_aberration: f32,
+    /// Trace back faces into their own room instead of a mirrored one, for partitions seen
+    /// from both sides. Needs `double_sided: true` and `cull_mode: None` on the base material.
+    pub double_sided_interior: u32,
+    /// Shape of the interior box seen through each window, e.g. `(2.0, 1.0)` for a wide room.
+    /// Only the ratio matters, the smaller side spans the window and the other side is
+    /// stretched past it. Applied on top of `box_min`/`box_max`; the back wall stays at `depth`
+    /// and its perspective (`depth_scale` in the shader) is unchanged.
+    pub room_aspect: Vec2,
+    /// Strength of the room reflected in a glossy floor, `0.0` disables it.
+    pub floor_gloss: f32,
+    /// Fraction of rooms with a window in their back wall letting daylight through, `0.0` for none.
+    pub far_window: f32,
+    /// Color of the daylight through the back wall windows, `w` scales its brightness.
+    pub far_window_color: Vec4,
+    /// Use the base color atlas alpha as the lit mask of the rooms instead of `emissive_texture`.
+    /// The mask is scaled by `emissive` of the base material and the instance tint. The alpha
+    /// then no longer holds the room depth, `depth`/`depth_world` are always used.
+    pub emissive_from_base_alpha: u32,
+    /// Faint ambient light inside unlit rooms so they don't look like black holes, `w` scales
+    /// it. It's a floor on the room glow, so lit rooms aren't washed out.
+    pub interior_ambient: Vec4,
+    /// Darken the side walls, ceiling and floor of the room box so its corners read, for
+    /// atlases with flat lit rooms.
+    pub shade_box_faces: u32,
+    /// Frames per second of the `occupant_texture` animation.
+    pub occupant_fps: f32,
+    /// Number of frames in the `occupant_texture` sprite sheet, laid out in a single row.
+    pub occupant_frames: u32,
+    /// Fraction of rooms with an occupant.
+    pub occupant_chance: f32,
+    /// Size of the back wall relative to the window, `< 1.0` gives a trapezoidal
+    /// forced-perspective room that narrows towards the back.
+    pub back_wall_scale: Vec2,
+    /// The base color atlas holds linear colors but is loaded as an sRGB texture (the default for
+    /// images), undo the sRGB decode of its samples so the colors aren't darkened.
+    pub base_color_is_linear: u32,
+    /// Frosted glass: blur radius of the interior in room cells, `0.0` disables it.
+    pub frost_amount: f32,
+    /// Number of samples of the `frost_amount` blur, fewer are cheaper but grainier.
+    pub frost_taps: u32,
+    /// Random per-room change of `depth`, each room's depth is in `depth ± depth_variation`.
+    /// Doesn't apply to rooms with their depth in the atlas alpha or to `depth_world`.
+    pub depth_variation: f32,
+    /// Fraction of room cells that are solid wall instead of a window, drawn with the plain base
+    /// material without an interior. Picked per cell independently of the lit rooms.
+    pub solid_threshold: f32,
+    /// Brighten the rooms where the primary directional light shines in through the window, the
+    /// more directly it shines at the wall the deeper it reaches into the room. `0.0` disables it.
+    /// Forward rendering only, the prepasses (and so deferred rendering) don't have the lights.
+    pub sun_penetration: f32,
+    /// Address mode of the room atlas samplers (`base_color_texture` and `emissive_texture`)
+    /// along U. The default clamps so mipmaps don't bleed between the atlas edges, like
+    /// Bevy's default sampler. Changes the sampler of the atlas `Image`s, see [`AtlasAddressMode`].
+    pub atlas_address_mode_u: AtlasAddressMode,
+    /// Address mode of the room atlas samplers along V, see `atlas_address_mode_u`.
+    pub atlas_address_mode_v: AtlasAddressMode,
+    /// Quality tier of the shader, see [`QualityTier`] for what each tier enables.
+    pub quality: QualityTier,
+    /// Darken the interior towards the corners of each room, `1.0` makes the corners black.
+    /// Measured in the room's own UV so it looks the same for any `rooms` count. `0.0` disables it.
+    pub room_vignette: f32,
+    /// Mirror every other room cell, alternating along both axes, so neighbouring rooms share
+    /// their walls seamlessly. Only rooms picking the same atlas room line up, use a single room
+    /// atlas (`atlas_rooms` of `1x1`) for continuous open-plan tiling.
+    pub mirror_tiling: u32,
+    /// Reflect the sky gradient of `sky_top_color` and `sky_bottom_color` in the glass when the
+    /// camera has no `EnvironmentMapLight`, a fallback for apps without image based lighting.
+    /// With an environment map Bevy's lighting reflects it instead. Forward rendering only.
+    pub reflect_environment: u32,
+    /// Color of the sky straight up for `reflect_environment` and `far_window_sky`, the alpha
+    /// is its intensity. Fades into `sky_bottom_color` straight down.
+    pub sky_top_color: Vec4,
+    /// Color of the sky (the ground) straight down for `reflect_environment` and
+    /// `far_window_sky`, the alpha is its intensity.
+    pub sky_bottom_color: Vec4,
+    /// Authoring aid: show the whole room atlas stretched flat across the surface (per
+    /// `uv_tiling` range) with its room cells outlined, without the interior. Forward rendering
+    /// only, the prepasses still write the interior.
+    pub atlas_debug: u32,
+    /// Brightness of the lit rooms' glow, multiplies the base material's `emissive`. Keep
+    /// `emissive` at white (or a light color) and set the brightness here, e.g. `10.0`, so glow
+    /// levels are comparable between materials.
+    pub emission_intensity: f32,
+    /// Brightness of a light fixture in the middle of the ceiling of lit rooms, glowing in the
+    /// light's color and lighting the nearby walls. `0.0` disables it.
+    pub ceiling_light: f32,
+    /// Turn the room box away from the window around the surface's up axis (the bitangent), in
+    /// radians. Positive angles turn the back wall towards +U (the tangent). Give the panes
+    /// of an angled or bay window each their own angle so they all look into a common room.
+    pub interior_rotation: f32,
+    /// End the rooms of the cells on the edges of the surface at the edge, so rooms stretched past
+    /// their window (`room_aspect`, `box_min`/`box_max`) don't leak into the interior of the
+    /// adjoining wall on building corners. The edges are those of the `uv_tiling` range.
+    pub clip_at_edges: u32,
+    /// Darken the interior by the base material's `occlusion_texture` at the surface UV, so dirt
+    /// and shadows painted on the wall carry over the windows. `1.0` applies the full occlusion,
+    /// `0.0` disables it.
+    pub ao_affects_interior: f32,
+    /// Quantize the interior colors to this many levels per channel, in sRGB, for a retro look
+    /// together with a nearest atlas sampler. `0` or `1` disables it.
+    pub color_levels: u32,
+    /// Offset of the `emissive_texture` samples from the base color atlas, in atlas UV, to line up
+    /// an emissive atlas exported slightly misaligned so the glow doesn't spill past the windows.
+    pub emissive_uv_offset: Vec2,
+    /// Strength of the `planar_reflection_texture` in the glass, `1.0` shows only the reflection.
+    pub glass_reflectance: f32,
+    /// Vary the rooms per entity: a seed from the entity's world position, scaled by this, is added
+    /// to the room, emission and flicker seeds, so buildings sharing a material get their own room
+    /// layout and lit rooms. `0.0` gives every entity the same rooms. Moving an entity changes its
+    /// rooms.
+    pub world_seed_scale: f32,
+    /// Position in the `emissive_layers`, cross-fading between the two nearest layers, e.g. `1.5`
+    /// is halfway between layer 1 and 2. Clamped to the layers, drive it by the time of day.
+    pub emissive_layer: f32,
+    /// Back wall window of the `far_window` rooms: its top left corner in `xy` and its size in
+    /// `zw`, in the UV of the back wall (`0..1` across it, `y` down like the atlas).
+    pub far_window_rect: Vec4,
+    /// See through the `far_window` rooms: their back wall window is a hole showing the sky
+    /// gradient of `sky_top_color` and `sky_bottom_color` along the view, instead of glowing with
+    /// `far_window_color`.
+    pub far_window_sky: u32,
+    /// Apply the base material's `normal_map_texture` when non-zero, `0` shades with the vertex
+    /// normal instead, e.g. to compare with and without the normal map on one material. No effect
+    /// without a normal map.
+    pub use_normal_map: u32,
+    /// Anisotropic glass, e.g. brushed or streaked: stretch the highlights and the sky reflection
+    /// of the glass along `glass_aniso_direction`, from `0.0` (isotropic, the default) to `1.0`.
+    /// Not on the wall cells and mullions, and only in forward rendering, Bevy's deferred lighting
+    /// has no anisotropy.
+    pub glass_anisotropy: f32,
+    /// Direction of the `glass_anisotropy` streaks in the wall's tangent space, `(1, 0)` along the
+    /// UV x axis.
+    pub glass_aniso_direction: Vec2,
+    /// Seed of the per-room animations, like the frame each room's occupant starts at, so new
+    /// animation patterns don't change the rooms or which are lit, see `room_seed`.
+    pub flicker_seed: f32,
+    /// Darken the room seen through the window where the sun (the first `DirectionalLight`) is
+    /// shadowed at the wall, e.g. by a tree or an awning, to about a third in full shadow. Needs
+    /// the shadows of the light and a shadow receiving mesh. Bevy's lighting already shadows the
+    /// glass highlights. Forward rendering only.
+    pub receive_exterior_shadows: u32,
+    /// Facades made of one UV island per window: each island spanning the `uv_tiling` range is one
+    /// room, `rooms` and `rooms_jitter` are ignored. The room of each island is picked by where
+    /// the island's UV origin is in the world, found from the UV derivatives, so the islands must
+    /// be flat and at least a quarter of their width apart. Derivatives across an island's edge
+    /// can show a pixel wide seam of another room, pad the islands with solid wall.
+    pub uv_island_rooms: u32,
+    /// Fraction of the lit rooms with a TV on, their glow flickering between bluish whites over
+    /// time, `0.0` (the default) for none. The TV rooms are picked with `flicker_seed`.
+    pub tv_flicker: f32,
+    /// Blend the interior into the wall's base color within `border_blend` of the room cell
+    /// edges, in cell UV units like `reveal_width`, softening the seam where the atlas doesn't
+    /// match the wall. `0.0` (the default) keeps a hard edge. Unlike `reveal_color` it fades the
+    /// room out, glass reflections and glow included.
+    pub border_blend: f32,
+    /// Fraction of the room depth clipped off the front of the room box, from `0.0` (the default)
+    /// to below `1.0`: the interior ray starts that far in, so grazing views don't sample the thin
+    /// sliver of side walls next to the window.
+    pub near_clip: f32,
+    /// Per-instance parameter table, see [`FakeInteriorInstances`].
+    #[cfg(feature = "instance_params")]
+    #[storage(101, read_only)]
+    pub instances: Handle<ShaderStorageBuffer>,
+    /// Sprite sheet of people silhouettes composited on the back wall of occupied rooms.
+    /// Occupants are disabled without it.
+    #[cfg_attr(feature = "extra_textures", texture(102), sampler(103))]
+    pub occupant_texture: Option<Handle<Image>>,
+    /// Palette of room light colors in a single row, e.g. 16x1. Each lit room picks one of
+    /// its pixels by a hash of the room and `emission_seed`, which tints its glow. The glow
+    /// isn't tinted without it.
+    #[cfg_attr(feature = "extra_textures", texture(104))]
+    pub light_palette_texture: Option<Handle<Image>>,
+    /// Mask of the modeled or painted mullions splitting each window, in the UV of a room cell
+    /// (`0..1` across the window). Where its alpha is opaque the window shows the plain base
+    /// material (the wall) instead of the interior, unlike an alpha cutout of the frame.
+    /// Repeated in every room cell and not mirrored by `mirror_tiling`.
+    #[cfg_attr(feature = "extra_textures", texture(105), sampler(106))]
+    pub mullion_mask_texture: Option<Handle<Image>>,
+    /// Rooms as the layers of an array texture instead of packed in the `base_color_texture`
+    /// atlas, built from separate room images by [`RoomImageArrays`]. Each room cell picks a
+    /// layer by its hash, `atlas_rooms` is ignored. The `emissive_texture` is then one lit
+    /// mask shared by all rooms, use `emissive_from_base_alpha` for a mask per room image.
+    #[cfg_attr(
+        feature = "extra_textures",
+        texture(107, dimension = "2d_array"),
+        sampler(108)
+    )]
+    pub room_images: Option<Handle<Image>>,
+    /// Which atlas room each room cell shows, for hand-designed facades: one texel per cell
+    /// (x along U, y along V), repeated when smaller than the `rooms` grid. The red channel
+    /// times 255 is the index of the atlas room, in rows of `atlas_rooms`, or the layer of
+    /// `room_images`. Cells with a transparent texel (alpha below `0.5`) keep their random room.
+    /// Read without a sampler, use a linear format like `Rgba8Unorm` so the indices aren't
+    /// decoded as sRGB.
+    #[cfg_attr(feature = "extra_textures", texture(109))]
+    pub room_index_map: Option<Handle<Image>>,
+    /// Planar reflection of the scene in front of the wall, rendered by the app from a camera
+    /// mirrored by the wall's plane, so it's horizontally flipped. Blended into the glass by
+    /// `glass_reflectance`, sampled at the fragment's screen position, so it needs the size of
+    /// the main camera's viewport. Forward rendering only. The shader only reads it when it's
+    /// set and `glass_reflectance > 0.0` (another pipeline), rendering the reflection is the
+    /// expensive part, see the `planar_reflection` example.
+    #[cfg_attr(feature = "extra_textures", texture(110), sampler(111))]
+    pub planar_reflection_texture: Option<Handle<Image>>,
+    /// Emissive atlases as the layers of an array texture, e.g. the lit rooms in the morning,
+    /// evening and at night, used instead of the `emissive_texture` and cross-faded by
+    /// `emissive_layer`. [`RoomImageArrays`] builds it from separate images.
+    #[cfg_attr(
+        feature = "extra_textures",
+        texture(112, dimension = "2d_array"),
+        sampler(113)
+    )]
+    pub emissive_layers: Option<Handle<Image>>,
 }
 
 impl Default for FakeInteriorMaterial {
@@ -30,10 +343,356 @@ impl Default for FakeInteriorMaterial {
             room_seed: 1.0,
             emission_seed: 1.0,
             emission_threshold: 0.5,
+            depth_world: 1.0,
+            use_depth_world: 0,
+            uv_tiling: Vec2::new(1.0, 1.0),
+            night_only: 0,
+            box_min: Vec3::new(-1.0, -1.0, -1.0),
+            box_max: Vec3::new(1.0, 1.0, 1.0),
+            reveal_color: Vec4::ZERO,
+            reveal_width: 0.02,
+            rooms_jitter: Vec2::ZERO,
+            chromatic_aberration: 0.0,
+            double_sided_interior: 0,
+            room_aspect: Vec2::ONE,
+            floor_gloss: 0.0,
+            far_window: 0.0,
+            far_window_color: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            emissive_from_base_alpha: 0,
+            interior_ambient: Vec4::new(1.0, 1.0, 1.0, 0.02),
+            shade_box_faces: 0,
+            occupant_fps: 8.0,
+            occupant_frames: 1,
+            occupant_chance: 0.25,
+            back_wall_scale: Vec2::ONE,
+            base_color_is_linear: 0,
+            frost_amount: 0.0,
+            frost_taps: 8,
+            depth_variation: 0.0,
+            solid_threshold: 0.0,
+            sun_penetration: 0.0,
+            atlas_address_mode_u: AtlasAddressMode::ClampToEdge,
+            atlas_address_mode_v: AtlasAddressMode::ClampToEdge,
+            quality: QualityTier::High,
+            room_vignette: 0.0,
+            mirror_tiling: 0,
+            reflect_environment: 0,
+            sky_top_color: Vec4::new(0.6, 0.6, 0.6, 1.0),
+            sky_bottom_color: Vec4::new(0.3, 0.3, 0.3, 1.0),
+            atlas_debug: 0,
+            emission_intensity: 1.0,
+            ceiling_light: 0.0,
+            interior_rotation: 0.0,
+            clip_at_edges: 0,
+            ao_affects_interior: 0.0,
+            color_levels: 0,
+            emissive_uv_offset: Vec2::ZERO,
+            glass_reflectance: 0.0,
+            world_seed_scale: 0.0,
+            emissive_layer: 0.0,
+            far_window_rect: Vec4::new(0.25, 0.25, 0.5, 0.5),
+            far_window_sky: 0,
+            use_normal_map: 1,
+            glass_anisotropy: 0.0,
+            glass_aniso_direction: Vec2::X,
+            flicker_seed: 1.0,
+            receive_exterior_shadows: 0,
+            uv_island_rooms: 0,
+            tv_flicker: 0.0,
+            border_blend: 0.0,
+            near_clip: 0.0,
+            #[cfg(feature = "instance_params")]
+            instances: FAKE_INTERIOR_INSTANCES_HANDLE,
+            occupant_texture: None,
+            light_palette_texture: None,
+            mullion_mask_texture: None,
+            room_images: None,
+            room_index_map: None,
+            planar_reflection_texture: None,
+            emissive_layers: None,
+        }
+    }
+}
+
+impl FakeInteriorMaterial {
+    /// Maximum `rooms` along each axis. More rooms hash into noise and are smaller than a
+    /// pixel at any sensible view distance, use `uv_tiling` for very large surfaces.
+    pub const MAX_ROOMS: f32 = 256.0;
+
+    /// `rooms` for a wall of `wall_size` with windows of `window_size`, e.g. both in meters.
+    ///
+    /// The count is rounded to whole rooms, which stretches the windows slightly to fill the
+    /// wall, and is at least one room on each axis.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_fake_interior::FakeInteriorMaterial;
+    /// let window = Vec2::new(2.0, 3.0);
+    /// let rooms = FakeInteriorMaterial::rooms_for_world_size(Vec2::new(20.0, 9.0), window);
+    /// assert_eq!(rooms, Vec2::new(10.0, 3.0));
+    /// // windows larger than the wall.
+    /// let rooms = FakeInteriorMaterial::rooms_for_world_size(Vec2::new(1.5, 2.0), window);
+    /// assert_eq!(rooms, Vec2::ONE);
+    /// ```
+    pub fn rooms_for_world_size(wall_size: Vec2, window_size: Vec2) -> Vec2 {
+        let rooms = (wall_size / window_size).round();
+        // also catches a zero `window_size`.
+        Vec2::select(rooms.is_finite_mask(), rooms, Vec2::ONE).max(Vec2::ONE)
+    }
+
+    /// `rooms` as used by the shader, clamped to [`MAX_ROOMS`](Self::MAX_ROOMS).
+    pub(crate) fn shader_rooms(&self) -> Vec2 {
+        self.rooms.min(Vec2::splat(Self::MAX_ROOMS))
+    }
+
+    /// Returns the room cell that a surface `uv` falls into, like the shader: the `uv` scaled
+    /// to the room grid and floored, with fractional `rooms` as well.
+    ///
+    /// The cells are counted from the surface's UV origin. A `uv` outside the surface's UV
+    /// range (`0..uv_tiling`) is in a cell outside `0..rooms`, the shader shows those as rooms
+    /// of their own. A `uv` on the boundary between two cells belongs to the second one, so
+    /// the far edge of the surface is cell `rooms`. `rooms_jitter` depends on the entity's
+    /// transform and isn't applied here.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_fake_interior::FakeInteriorMaterial;
+    /// let material = FakeInteriorMaterial {
+    ///     rooms: Vec2::new(4.0, 2.0),
+    ///     ..default()
+    /// };
+    /// assert_eq!(material.cell_at_uv(Vec2::new(0.3, 0.7)), IVec2::new(1, 1));
+    /// // on the boundaries.
+    /// assert_eq!(material.cell_at_uv(Vec2::ZERO), IVec2::ZERO);
+    /// assert_eq!(material.cell_at_uv(Vec2::new(0.25, 0.5)), IVec2::new(1, 1));
+    /// assert_eq!(material.cell_at_uv(Vec2::ONE), IVec2::new(4, 2));
+    /// // outside of the surface.
+    /// assert_eq!(material.cell_at_uv(Vec2::new(-0.1, -0.6)), IVec2::new(-1, -2));
+    /// assert_eq!(material.cell_at_uv(Vec2::new(1.3, 2.2)), IVec2::new(5, 4));
+    ///
+    /// // 3x tiled UVs cover the same grid.
+    /// let tiled = FakeInteriorMaterial {
+    ///     uv_tiling: Vec2::splat(3.0),
+    ///     ..material.clone()
+    /// };
+    /// assert_eq!(tiled.cell_at_uv(Vec2::new(0.9, 2.1)), IVec2::new(1, 1));
+    /// // fractional rooms, the last cell is cut off by the surface edge.
+    /// let fractional = FakeInteriorMaterial {
+    ///     rooms: Vec2::new(2.5, 1.0),
+    ///     ..default()
+    /// };
+    /// assert_eq!(fractional.cell_at_uv(Vec2::new(0.99, 0.5)), IVec2::new(2, 0));
+    /// ```
+    pub fn cell_at_uv(&self, uv: Vec2) -> IVec2 {
+        let rooms = if self.uv_island_rooms != 0 {
+            Vec2::ONE
+        } else {
+            self.shader_rooms()
+        };
+        (uv / self.uv_tiling * rooms).floor().as_ivec2()
+    }
+
+    /// Returns which rooms have their light on, matching the shader's decision from
+    /// `emission_seed` and `emission_threshold`.
+    ///
+    /// The rooms are in rows, the room of [`cell_at_uv`](Self::cell_at_uv) `cell` is at
+    /// `cell.x + cell.y * rooms.x`. Per-instance overrides, `rooms_jitter`, `world_seed_scale`,
+    /// `uv_island_rooms` and the lit mask of the emissive texture aren't applied.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_fake_interior::FakeInteriorMaterial;
+    /// let material = FakeInteriorMaterial {
+    ///     rooms: Vec2::splat(32.0),
+    ///     emission_threshold: 0.3,
+    ///     ..default()
+    /// };
+    /// let lit = material.lit_rooms();
+    /// assert_eq!(lit.len(), 32 * 32);
+    /// let fraction = lit.iter().filter(|lit| **lit).count() as f32 / lit.len() as f32;
+    /// assert!((fraction - 0.3).abs() < 0.05, "{fraction}");
+    /// ```
+    pub fn lit_rooms(&self) -> Vec<bool> {
+        let rooms = self.shader_rooms();
+        let room_count = rooms.max(Vec2::ONE).floor().as_uvec2();
+        (0..room_count.y)
+            .flat_map(|y| (0..room_count.x).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                // same float room index as the shader.
+                let room_index = x as f32 + y as f32 * rooms.x;
+                room_random(room_index, self.emission_seed) < self.emission_threshold
+            })
+            .collect()
+    }
+
+    /// Interpolates the numeric parameters towards `other`, e.g. to animate a transition.
+    ///
+    /// Textures can't be interpolated, so both materials must use the same base textures.
+    /// The `u32` switches and the instance table are taken from `other` once `t >= 0.5`.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_fake_interior::FakeInteriorMaterial;
+    /// let a = FakeInteriorMaterial { depth: 0.2, ..default() };
+    /// let b = FakeInteriorMaterial { depth: 0.6, rooms: Vec2::splat(3.0), ..default() };
+    ///
+    /// assert!((a.lerp(&b, 0.0).depth - 0.2).abs() < 1e-6);
+    /// assert_eq!(a.lerp(&b, 0.0).rooms, Vec2::ONE);
+    /// assert!((a.lerp(&b, 0.5).depth - 0.4).abs() < 1e-6);
+    /// assert_eq!(a.lerp(&b, 0.5).rooms, Vec2::splat(2.0));
+    /// assert!((a.lerp(&b, 1.0).depth - 0.6).abs() < 1e-6);
+    /// assert_eq!(a.lerp(&b, 1.0).rooms, Vec2::splat(3.0));
+    /// ```
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let step = if t < 0.5 { self } else { other };
+        Self {
+            atlas_rooms: self.atlas_rooms.lerp(other.atlas_rooms, t),
+            rooms: self.rooms.lerp(other.rooms, t),
+            depth: self.depth.lerp(other.depth, t),
+            room_seed: self.room_seed.lerp(other.room_seed, t),
+            emission_seed: self.emission_seed.lerp(other.emission_seed, t),
+            emission_threshold: self.emission_threshold.lerp(other.emission_threshold, t),
+            depth_world: self.depth_world.lerp(other.depth_world, t),
+            uv_tiling: self.uv_tiling.lerp(other.uv_tiling, t),
+            box_min: self.box_min.lerp(other.box_min, t),
+            box_max: self.box_max.lerp(other.box_max, t),
+            reveal_color: self.reveal_color.lerp(other.reveal_color, t),
+            reveal_width: self.reveal_width.lerp(other.reveal_width, t),
+            rooms_jitter: self.rooms_jitter.lerp(other.rooms_jitter, t),
+            chromatic_aberration: self
+                .chromatic_aberration
+                .lerp(other.chromatic_aberration, t),
+            room_aspect: self.room_aspect.lerp(other.room_aspect, t),
+            floor_gloss: self.floor_gloss.lerp(other.floor_gloss, t),
+            far_window: self.far_window.lerp(other.far_window, t),
+            far_window_color: self.far_window_color.lerp(other.far_window_color, t),
+            interior_ambient: self.interior_ambient.lerp(other.interior_ambient, t),
+            occupant_fps: self.occupant_fps.lerp(other.occupant_fps, t),
+            occupant_chance: self.occupant_chance.lerp(other.occupant_chance, t),
+            back_wall_scale: self.back_wall_scale.lerp(other.back_wall_scale, t),
+            frost_amount: self.frost_amount.lerp(other.frost_amount, t),
+            depth_variation: self.depth_variation.lerp(other.depth_variation, t),
+            solid_threshold: self.solid_threshold.lerp(other.solid_threshold, t),
+            sun_penetration: self.sun_penetration.lerp(other.sun_penetration, t),
+            room_vignette: self.room_vignette.lerp(other.room_vignette, t),
+            sky_top_color: self.sky_top_color.lerp(other.sky_top_color, t),
+            sky_bottom_color: self.sky_bottom_color.lerp(other.sky_bottom_color, t),
+            emission_intensity: self.emission_intensity.lerp(other.emission_intensity, t),
+            ceiling_light: self.ceiling_light.lerp(other.ceiling_light, t),
+            interior_rotation: self.interior_rotation.lerp(other.interior_rotation, t),
+            ao_affects_interior: self.ao_affects_interior.lerp(other.ao_affects_interior, t),
+            emissive_uv_offset: self.emissive_uv_offset.lerp(other.emissive_uv_offset, t),
+            glass_reflectance: self.glass_reflectance.lerp(other.glass_reflectance, t),
+            world_seed_scale: self.world_seed_scale.lerp(other.world_seed_scale, t),
+            emissive_layer: self.emissive_layer.lerp(other.emissive_layer, t),
+            far_window_rect: self.far_window_rect.lerp(other.far_window_rect, t),
+            glass_anisotropy: self.glass_anisotropy.lerp(other.glass_anisotropy, t),
+            glass_aniso_direction: self
+                .glass_aniso_direction
+                .lerp(other.glass_aniso_direction, t),
+            flicker_seed: self.flicker_seed.lerp(other.flicker_seed, t),
+            tv_flicker: self.tv_flicker.lerp(other.tv_flicker, t),
+            border_blend: self.border_blend.lerp(other.border_blend, t),
+            near_clip: self.near_clip.lerp(other.near_clip, t),
+            ..step.clone()
+        }
+    }
+
+    /// Assembles a [`StandardFakeInteriorMaterial`] with `extension`, loading its room atlas
+    /// from the `base` path and its emissive atlas from the `emissive` path.
+    ///
+    /// The emissive color is white, set `emission_intensity` for brighter room lights.
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_fake_interior::*;
+    /// fn setup(
+    ///     asset_server: Res<AssetServer>,
+    ///     mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    /// ) {
+    ///     let interior = FakeInteriorMaterial::from_paths(
+    ///         &asset_server,
+    ///         "textures/rooms_depth.png",
+    ///         "textures/rooms_emit.png",
+    ///         FakeInteriorMaterial {
+    ///             atlas_rooms: Vec2::new(3.0, 2.0),
+    ///             emission_intensity: 10.0,
+    ///             ..default()
+    ///         },
+    ///     );
+    ///     let interior = interiors.add(interior);
+    /// }
+    /// ```
+    pub fn from_paths(
+        asset_server: &AssetServer,
+        base: &str,
+        emissive: &str,
+        extension: Self,
+    ) -> StandardFakeInteriorMaterial {
+        StandardFakeInteriorMaterial {
+            base: StandardMaterial {
+                base_color_texture: Some(asset_server.load(base.to_string())),
+                emissive: LinearRgba::WHITE,
+                emissive_texture: Some(asset_server.load(emissive.to_string())),
+                ..default()
+            },
+            extension,
         }
     }
 }
 
+/// Constructors of [`StandardFakeInteriorMaterial`], which is an alias of Bevy's
+/// `ExtendedMaterial` and can't have its own `Default`.
+pub trait StandardFakeInteriorMaterialExt {
+    /// A fake interior with window glass defaults for the base material: white `emissive`
+    /// (the room glow is set by `emission_intensity`) and a `reflectance` of `0.2`, with the
+    /// default [`FakeInteriorMaterial`]. Set the room atlas textures on `base`.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_fake_interior::*;
+    /// let material = StandardFakeInteriorMaterial::fake_default();
+    /// assert_eq!(material.base.emissive, LinearRgba::WHITE);
+    /// assert_eq!(material.base.reflectance, 0.2);
+    /// assert!(material.base.base_color_texture.is_none());
+    /// assert_eq!(material.extension.depth, FakeInteriorMaterial::default().depth);
+    /// ```
+    fn fake_default() -> Self;
+}
+
+impl StandardFakeInteriorMaterialExt for StandardFakeInteriorMaterial {
+    fn fake_default() -> Self {
+        Self {
+            base: StandardMaterial {
+                emissive: LinearRgba::WHITE,
+                reflectance: 0.2,
+                ..default()
+            },
+            extension: FakeInteriorMaterial::default(),
+        }
+    }
+}
+
+/// PCG integer hash, so the CPU matches the shader exactly. Keep in sync with
+/// `fake_interior.wgsl`.
+fn pcg_hash(v: u32) -> u32 {
+    let state = v.wrapping_mul(747796405).wrapping_add(2891336453);
+    let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277803737);
+    (word >> 22) ^ word
+}
+
+/// Random `0..1` value of a room for a seed.
+fn room_random(room_index: f32, seed: f32) -> f32 {
+    let h = pcg_hash(room_index as i32 as u32 ^ pcg_hash(seed.to_bits()));
+    (h >> 8) as f32 / 16777216.0
+}
+
+/// Uniform of the [`FakeInteriorMaterial`], the `FakeInteriorMaterial` struct in
+/// `fake_interior.wgsl`.
+///
+/// `ShaderType` lays the fields out with the WGSL alignment rules, so no manual padding is
+/// needed, but the names, types and order must match the WGSL struct. This is checked by
+/// `tests/shader_validation.rs`.
 #[derive(Clone, Default, ShaderType)]
 pub struct FakeInteriorMaterialUniform {
     pub atlas_rooms: Vec2,
@@ -42,37 +701,366 @@ pub struct FakeInteriorMaterialUniform {
     pub room_seed: f32,
     pub emission_seed: f32,
     pub emission_threshold: f32,
+    pub depth_world: f32,
+    pub use_depth_world: u32,
+    pub uv_tiling: Vec2,
+    pub night_only: u32,
+    pub box_min: Vec3,
+    pub box_max: Vec3,
+    pub reveal_color: Vec4,
+    pub reveal_width: f32,
+    pub rooms_jitter: Vec2,
+    pub chromatic_aberration: f32,
+    pub double_sided_interior: u32,
+    pub room_aspect: Vec2,
+    pub floor_gloss: f32,
+    pub far_window: f32,
+    pub far_window_color: Vec4,
+    pub emissive_from_base_alpha: u32,
+    pub interior_ambient: Vec4,
+    pub shade_box_faces: u32,
+    pub occupant_fps: f32,
+    pub occupant_frames: u32,
+    pub occupant_chance: f32,
+    pub back_wall_scale: Vec2,
+    pub base_color_is_linear: u32,
+    pub frost_amount: f32,
+    pub frost_taps: u32,
+    pub depth_variation: f32,
+    pub solid_threshold: f32,
+    pub sun_penetration: f32,
+    pub room_vignette: f32,
+    pub mirror_tiling: u32,
+    pub reflect_environment: u32,
+    pub sky_top_color: Vec4,
+    pub sky_bottom_color: Vec4,
+    pub atlas_debug: u32,
+    pub emission_intensity: f32,
+    pub ceiling_light: f32,
+    pub interior_rotation: f32,
+    pub clip_at_edges: u32,
+    pub ao_affects_interior: f32,
+    pub color_levels: u32,
+    pub emissive_uv_offset: Vec2,
+    pub glass_reflectance: f32,
+    pub world_seed_scale: f32,
+    pub emissive_layer: f32,
+    pub far_window_rect: Vec4,
+    pub far_window_sky: u32,
+    pub use_normal_map: u32,
+    pub glass_anisotropy: f32,
+    pub glass_aniso_direction: Vec2,
+    pub flicker_seed: f32,
+    pub receive_exterior_shadows: u32,
+    pub uv_island_rooms: u32,
+    pub tv_flicker: f32,
+    pub border_blend: f32,
+    pub near_clip: f32,
+}
+
+/// Copies the parameters into the uniform, also usable without a render context. Bevy's
+/// `AsBindGroupShaderType` is implemented through this for the material's `#[uniform]`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_fake_interior::{FakeInteriorMaterial, FakeInteriorMaterialUniform};
+/// let material = FakeInteriorMaterial { depth: 0.3, ..default() };
+/// let uniform = FakeInteriorMaterialUniform::from(&material);
+/// assert_eq!(uniform.depth, 0.3);
+/// // `rooms` beyond `MAX_ROOMS` are clamped.
+/// let huge = FakeInteriorMaterial { rooms: Vec2::splat(1000.0), ..default() };
+/// let max_rooms = Vec2::splat(FakeInteriorMaterial::MAX_ROOMS);
+/// assert_eq!(FakeInteriorMaterialUniform::from(&huge).rooms, max_rooms);
+/// ```
+impl From<&FakeInteriorMaterial> for FakeInteriorMaterialUniform {
+    fn from(material: &FakeInteriorMaterial) -> Self {
+        if material.rooms.max_element() > FakeInteriorMaterial::MAX_ROOMS {
+            warn_once!(
+                "FakeInteriorMaterial: rooms {} is clamped to at most {} per axis",
+                material.rooms,
+                FakeInteriorMaterial::MAX_ROOMS
+            );
+        }
+        Self {
+            atlas_rooms: material.atlas_rooms,
+            rooms: material.shader_rooms(),
+            depth: material.depth,
+            room_seed: material.room_seed,
+            emission_seed: material.emission_seed,
+            emission_threshold: material.emission_threshold,
+            depth_world: material.depth_world,
+            use_depth_world: material.use_depth_world,
+            uv_tiling: material.uv_tiling,
+            night_only: material.night_only,
+            box_min: material.box_min,
+            box_max: material.box_max,
+            reveal_color: material.reveal_color,
+            reveal_width: material.reveal_width,
+            rooms_jitter: material.rooms_jitter,
+            chromatic_aberration: material.chromatic_aberration,
+            double_sided_interior: material.double_sided_interior,
+            room_aspect: material.room_aspect,
+            floor_gloss: material.floor_gloss,
+            far_window: material.far_window,
+            far_window_color: material.far_window_color,
+            emissive_from_base_alpha: material.emissive_from_base_alpha,
+            interior_ambient: material.interior_ambient,
+            shade_box_faces: material.shade_box_faces,
+            occupant_fps: material.occupant_fps,
+            occupant_frames: material.occupant_frames,
+            occupant_chance: material.occupant_chance,
+            back_wall_scale: material.back_wall_scale,
+            base_color_is_linear: material.base_color_is_linear,
+            frost_amount: material.frost_amount,
+            frost_taps: material.frost_taps,
+            depth_variation: material.depth_variation,
+            solid_threshold: material.solid_threshold,
+            sun_penetration: material.sun_penetration,
+            room_vignette: material.room_vignette,
+            mirror_tiling: material.mirror_tiling,
+            reflect_environment: material.reflect_environment,
+            sky_top_color: material.sky_top_color,
+            sky_bottom_color: material.sky_bottom_color,
+            atlas_debug: material.atlas_debug,
+            emission_intensity: material.emission_intensity,
+            ceiling_light: material.ceiling_light,
+            interior_rotation: material.interior_rotation,
+            clip_at_edges: material.clip_at_edges,
+            ao_affects_interior: material.ao_affects_interior,
+            color_levels: material.color_levels,
+            emissive_uv_offset: material.emissive_uv_offset,
+            glass_reflectance: material.glass_reflectance,
+            world_seed_scale: material.world_seed_scale,
+            emissive_layer: material.emissive_layer,
+            far_window_rect: material.far_window_rect,
+            far_window_sky: material.far_window_sky,
+            use_normal_map: material.use_normal_map,
+            glass_anisotropy: material.glass_anisotropy,
+            glass_aniso_direction: material.glass_aniso_direction,
+            flicker_seed: material.flicker_seed,
+            receive_exterior_shadows: material.receive_exterior_shadows,
+            uv_island_rooms: material.uv_island_rooms,
+            tv_flicker: material.tv_flicker,
+            border_blend: material.border_blend,
+            near_clip: material.near_clip,
+        }
+    }
 }
 
-impl AsBindGroupShaderType<FakeInteriorMaterialUniform> for FakeInteriorMaterial {
-    fn as_bind_group_shader_type(
-        &self,
-        _images: &RenderAssets<GpuImage>,
-    ) -> FakeInteriorMaterialUniform {
-        FakeInteriorMaterialUniform {
-            atlas_rooms: self.atlas_rooms,
-            rooms: self.rooms,
-            depth: self.depth,
-            room_seed: self.room_seed,
-            emission_seed: self.emission_seed,
-            emission_threshold: self.emission_threshold,
+/// Pipeline key of the [`FakeInteriorMaterial`], the material fields that select shader defs.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct FakeInteriorMaterialKey {
+    /// Nothing glows: no room is lit (`emission_threshold <= 0.0` or
+    /// `emission_intensity <= 0.0`), no far windows
+    /// (`far_window <= 0.0`) and no `interior_ambient`. Compiles out the emissive work with
+    /// the `FAKE_INTERIOR_NO_EMISSIVE` shader def, for daytime only scenes.
+    pub no_emissive: bool,
+    /// See [`QualityTier`].
+    pub quality: QualityTier,
+    /// Blend the `planar_reflection_texture` into the glass (`glass_reflectance > 0.0`), with
+    /// the `FAKE_INTERIOR_PLANAR_REFLECTION` shader def.
+    pub planar_reflection: bool,
+    /// Anisotropic glass (`glass_anisotropy > 0.0`), with Bevy's `STANDARD_MATERIAL_ANISOTROPY`
+    /// and the `FAKE_INTERIOR_GLASS_ANISOTROPY` shader defs.
+    pub glass_anisotropy: bool,
+    /// `occupant_texture` is set, the `FAKE_INTERIOR_OCCUPANTS` shader def. Like the other
+    /// optional textures, only the pipelines of the materials that set it declare and sample
+    /// its bindings.
+    pub occupants: bool,
+    /// `light_palette_texture` is set, the `FAKE_INTERIOR_LIGHT_PALETTE` shader def.
+    pub light_palette: bool,
+    /// `mullion_mask_texture` is set, the `FAKE_INTERIOR_MULLION_MASK` shader def.
+    pub mullion_mask: bool,
+    /// `room_images` is set, the `FAKE_INTERIOR_ROOM_IMAGES` shader def.
+    pub room_images: bool,
+    /// `room_index_map` is set, the `FAKE_INTERIOR_ROOM_INDEX_MAP` shader def.
+    pub room_index_map: bool,
+    /// `emissive_layers` is set, the `FAKE_INTERIOR_EMISSIVE_LAYERS` shader def.
+    pub emissive_layers: bool,
+}
+
+impl From<&FakeInteriorMaterial> for FakeInteriorMaterialKey {
+    fn from(material: &FakeInteriorMaterial) -> Self {
+        Self {
+            no_emissive: (material.emission_threshold <= 0.0 || material.emission_intensity <= 0.0)
+                && material.far_window <= 0.0
+                && material.interior_ambient.w <= 0.0,
+            quality: material.quality,
+            planar_reflection: extra_texture(&material.planar_reflection_texture)
+                && material.glass_reflectance > 0.0,
+            glass_anisotropy: material.glass_anisotropy > 0.0,
+            occupants: extra_texture(&material.occupant_texture),
+            light_palette: extra_texture(&material.light_palette_texture),
+            mullion_mask: extra_texture(&material.mullion_mask_texture),
+            room_images: extra_texture(&material.room_images),
+            room_index_map: extra_texture(&material.room_index_map),
+            emissive_layers: extra_texture(&material.emissive_layers),
         }
     }
 }
 
+/// Whether an optional texture is set and bound, its bindings need the `extra_textures` feature.
+fn extra_texture(texture: &Option<Handle<Image>>) -> bool {
+    if cfg!(feature = "extra_textures") {
+        return texture.is_some();
+    }
+    if texture.is_some() {
+        warn_once!(
+            "FakeInteriorMaterial: its optional textures are ignored without the `extra_textures` feature"
+        );
+    }
+    false
+}
+
 impl MaterialExtension for FakeInteriorMaterial {
     fn fragment_shader() -> ShaderRef {
         "shaders/fake_interior.wgsl".into()
     }
+
+    fn prepass_fragment_shader() -> ShaderRef {
+        "shaders/fake_interior.wgsl".into()
+    }
+
+    fn deferred_fragment_shader() -> ShaderRef {
+        "shaders/fake_interior.wgsl".into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialExtensionPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        key: MaterialExtensionKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            // Depth only prepasses (shadows) don't have the normals needed to trace the
+            // interior and the interior doesn't change the depth, use the standard prepass.
+            if fragment.shader_defs.contains(&"PREPASS_PIPELINE".into())
+                && !fragment
+                    .shader_defs
+                    .contains(&"NORMAL_PREPASS_OR_DEFERRED_PREPASS".into())
+            {
+                fragment.shader = PBR_PREPASS_SHADER_HANDLE;
+                return Ok(());
+            }
+            // The main pass and the normal and deferred prepasses have `globals` in their view
+            // layout, without it the animations are frozen at time 0.
+            fragment.shader_defs.push("FAKE_INTERIOR_GLOBALS".into());
+            if key.bind_group_data.no_emissive {
+                fragment
+                    .shader_defs
+                    .push("FAKE_INTERIOR_NO_EMISSIVE".into());
+            }
+            if key.bind_group_data.planar_reflection {
+                fragment
+                    .shader_defs
+                    .push("FAKE_INTERIOR_PLANAR_REFLECTION".into());
+            }
+            if key.bind_group_data.glass_anisotropy {
+                // Bevy's anisotropic lighting, also set by an anisotropic base material.
+                let anisotropy = "STANDARD_MATERIAL_ANISOTROPY".into();
+                if !fragment.shader_defs.contains(&anisotropy) {
+                    fragment.shader_defs.push(anisotropy);
+                }
+                fragment
+                    .shader_defs
+                    .push("FAKE_INTERIOR_GLASS_ANISOTROPY".into());
+            }
+            // the optional textures the material sets.
+            let data = &key.bind_group_data;
+            let textures = [
+                (data.occupants, "FAKE_INTERIOR_OCCUPANTS"),
+                (data.light_palette, "FAKE_INTERIOR_LIGHT_PALETTE"),
+                (data.mullion_mask, "FAKE_INTERIOR_MULLION_MASK"),
+                (data.room_images, "FAKE_INTERIOR_ROOM_IMAGES"),
+                (data.room_index_map, "FAKE_INTERIOR_ROOM_INDEX_MAP"),
+                (data.emissive_layers, "FAKE_INTERIOR_EMISSIVE_LAYERS"),
+            ];
+            for (_, def) in textures.into_iter().filter(|(set, _)| *set) {
+                fragment.shader_defs.push(def.into());
+            }
+            match key.bind_group_data.quality {
+                QualityTier::Low => {
+                    fragment.shader_defs.push("FAKE_INTERIOR_FLAT".into());
+                    fragment.shader_defs.push("FAKE_INTERIOR_NO_DETAILS".into());
+                }
+                QualityTier::Medium => {
+                    fragment.shader_defs.push("FAKE_INTERIOR_NO_DETAILS".into());
+                }
+                QualityTier::High => {}
+            }
+            #[cfg(feature = "instance_params")]
+            fragment
+                .shader_defs
+                .push("FAKE_INTERIOR_INSTANCE_PARAMS".into());
+        }
+        Ok(())
+    }
 }
 
-#[derive(Default, Clone, Debug)]
-pub struct FakeInteriorMaterialPlugin;
+/// Adds the [`StandardFakeInteriorMaterial`] and its [`FakeInteriorLoader`].
+///
+/// Can be added more than once, e.g. by several plugin groups, only the first one is used.
+///
+/// Animated details (e.g. occupants) are driven by Bevy's global time uniform in the shader,
+/// there are no per-material time updates. Culled walls aren't drawn, so off-screen
+/// interiors cost nothing to animate.
+#[derive(Clone, Debug)]
+pub struct FakeInteriorMaterialPlugin {
+    /// Render fake interior walls in the depth/normal prepass, forwarded to the
+    /// `MaterialPlugin`. Disable it to keep the walls out of the prepass textures.
+    pub prepass_enabled: bool,
+    /// Give materials without a `base_color_texture` the checkered room of
+    /// [`FAKE_INTERIOR_PLACEHOLDER_HANDLE`], so new materials show an interior right away.
+    /// Sets the texture on the material asset. On by default in debug builds.
+    pub use_placeholder: bool,
+}
+
+impl Default for FakeInteriorMaterialPlugin {
+    fn default() -> Self {
+        Self {
+            prepass_enabled: true,
+            use_placeholder: cfg!(debug_assertions),
+        }
+    }
+}
 
 impl Plugin for FakeInteriorMaterialPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(MaterialPlugin::<StandardFakeInteriorMaterial>::default())
-            .register_asset_reflect::<StandardFakeInteriorMaterial>()
-            .register_asset_reflect::<FakeInteriorMaterial>();
+        // `MaterialPlugin` needs the asset server, fail with a clear message instead of
+        // panicking inside of it when this plugin is added before `DefaultPlugins`.
+        assert!(
+            app.is_plugin_added::<AssetPlugin>(),
+            "FakeInteriorMaterialPlugin must be added after DefaultPlugins (or AssetPlugin)"
+        );
+        // plugin groups of a modular app may each add the plugin, only the first one counts.
+        if app.is_plugin_added::<MaterialPlugin<StandardFakeInteriorMaterial>>() {
+            debug!("FakeInteriorMaterialPlugin is already added, ignoring it");
+            return;
+        }
+        app.add_plugins(MaterialPlugin::<StandardFakeInteriorMaterial> {
+            prepass_enabled: self.prepass_enabled,
+            ..default()
+        })
+        .register_asset_reflect::<StandardFakeInteriorMaterial>()
+        .register_asset_reflect::<FakeInteriorMaterial>()
+        .init_asset_loader::<FakeInteriorLoader>();
+        atlas_sampler::init_atlas_samplers(app);
+        streamed_image::init_streamed_images(app);
+        room_images::init_room_images(app);
+        ready::init_ready(app);
+        lod::init_lod(app);
+        convert::init_convert(app);
+        if self.use_placeholder {
+            placeholder::init_placeholder(app);
+        }
+        #[cfg(feature = "instance_params")]
+        instance::init_instance_buffer(app);
+        #[cfg(feature = "diagnostics")]
+        diagnostics::init_diagnostics(app);
+    }
+
+    fn is_unique(&self) -> bool {
+        // adding it again is a no-op instead of a panic, see `build`.
+        false
     }
 }
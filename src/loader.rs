@@ -0,0 +1,217 @@
+//! Loads a [`StandardFakeInteriorMaterial`] from a `.fakeinterior.ron` file.
+
+use std::fmt;
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, LoadContext, LoadDirectError},
+    image::TextureAccessError,
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{
+    FakeInteriorEmissiveMask, FakeInteriorMaterial, FakeInteriorParams,
+    StandardFakeInteriorMaterial,
+};
+
+/// Contents of a `.fakeinterior.ron` file.
+///
+/// Every field is optional and defaults to the [`FakeInteriorMaterial`] and
+/// [`StandardMaterial`] defaults, `Option` fields are written as `Some(..)`. Texture paths are
+/// asset paths, like the ones passed to [`AssetServer::load`]. Unknown fields are an error.
+///
+/// The numeric material fields are the ones of [`FakeInteriorParams`], see
+/// [`params`](Self::params).
+///
+/// ```ron
+/// (
+///     base_color_texture: Some("textures/rooms_depth.png"),
+///     emissive_texture: Some("textures/rooms_emit.png"),
+///     emissive: (1.0, 1.0, 1.0),
+///     emission_intensity: 10.0,
+///     atlas_rooms: (3.0, 2.0),
+///     rooms: (6.0, 6.0),
+///     depth: 0.5,
+///     room_seed: 1.2,
+/// )
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct FakeInteriorDescription {
+    /// Room atlas, the alpha holds the room depth.
+    pub base_color_texture: Option<String>,
+    /// Lit mask of the room atlas.
+    pub emissive_texture: Option<String>,
+    /// Generate the lit mask from `base_color_texture` when there's no `emissive_texture`,
+    /// e.g. `Some((threshold: 0.6))`. The atlas is read on the CPU while loading.
+    pub emissive_from_base: Option<FakeInteriorEmissiveMask>,
+    /// Linear RGB emissive color of the lit rooms.
+    pub emissive: [f32; 3],
+    /// `reflectance` of the window glass.
+    pub reflectance: f32,
+    /// Sprite sheet for [`FakeInteriorMaterial::occupant_texture`].
+    pub occupant_texture: Option<String>,
+    pub atlas_rooms: [f32; 2],
+    pub rooms: [f32; 2],
+    pub depth: f32,
+    /// Room depth in world units, sets `use_depth_world` when present.
+    pub depth_world: Option<f32>,
+    pub room_seed: f32,
+    pub emission_seed: f32,
+    pub flicker_seed: f32,
+    pub emission_threshold: f32,
+    /// Brightness of the lit rooms, see [`FakeInteriorMaterial::emission_intensity`].
+    pub emission_intensity: f32,
+    pub uv_tiling: [f32; 2],
+}
+
+impl Default for FakeInteriorDescription {
+    fn default() -> Self {
+        let params = FakeInteriorParams::default();
+        let base = StandardMaterial::default();
+        Self {
+            base_color_texture: None,
+            emissive_texture: None,
+            emissive_from_base: None,
+            emissive: base.emissive.to_vec3().to_array(),
+            reflectance: base.reflectance,
+            occupant_texture: None,
+            atlas_rooms: params.atlas_rooms.to_array(),
+            rooms: params.rooms.to_array(),
+            depth: params.depth,
+            depth_world: None,
+            room_seed: params.room_seed,
+            emission_seed: params.emission_seed,
+            flicker_seed: params.flicker_seed,
+            emission_threshold: params.emission_threshold,
+            emission_intensity: params.emission_intensity,
+            uv_tiling: params.uv_tiling.to_array(),
+        }
+    }
+}
+
+impl FakeInteriorDescription {
+    /// The numeric material fields of this description.
+    pub fn params(&self) -> FakeInteriorParams {
+        FakeInteriorParams {
+            atlas_rooms: Vec2::from_array(self.atlas_rooms),
+            rooms: Vec2::from_array(self.rooms),
+            depth: self.depth,
+            room_seed: self.room_seed,
+            emission_seed: self.emission_seed,
+            flicker_seed: self.flicker_seed,
+            emission_threshold: self.emission_threshold,
+            emission_intensity: self.emission_intensity,
+            uv_tiling: Vec2::from_array(self.uv_tiling),
+        }
+    }
+}
+
+/// Error of the [`FakeInteriorLoader`].
+#[derive(Debug)]
+pub enum FakeInteriorLoaderError {
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    /// The file isn't a valid [`FakeInteriorDescription`].
+    Ron(ron::error::SpannedError),
+    /// The base color atlas to generate the lit mask from couldn't be loaded.
+    Atlas(LoadDirectError),
+    /// The lit mask couldn't be generated from the base color atlas, e.g. it's compressed.
+    EmissiveMask(TextureAccessError),
+}
+
+impl fmt::Display for FakeInteriorLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read fake interior: {err}"),
+            Self::Ron(err) => write!(f, "could not parse fake interior: {err}"),
+            Self::Atlas(err) => write!(f, "could not load the room atlas: {err}"),
+            Self::EmissiveMask(err) => write!(f, "could not generate the lit mask: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for FakeInteriorLoaderError {}
+
+impl From<std::io::Error> for FakeInteriorLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for FakeInteriorLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Ron(err)
+    }
+}
+
+impl From<LoadDirectError> for FakeInteriorLoaderError {
+    fn from(err: LoadDirectError) -> Self {
+        Self::Atlas(err)
+    }
+}
+
+impl From<TextureAccessError> for FakeInteriorLoaderError {
+    fn from(err: TextureAccessError) -> Self {
+        Self::EmissiveMask(err)
+    }
+}
+
+/// Loads `.fakeinterior.ron` files, see [`FakeInteriorDescription`] for the format.
+/// Added by the [`FakeInteriorMaterialPlugin`](crate::FakeInteriorMaterialPlugin).
+#[derive(Default)]
+pub struct FakeInteriorLoader;
+
+impl AssetLoader for FakeInteriorLoader {
+    type Asset = StandardFakeInteriorMaterial;
+    type Settings = ();
+    type Error = FakeInteriorLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let desc: FakeInteriorDescription = ron::de::from_bytes(&bytes)?;
+
+        let generated_emissive = match (&desc.emissive_from_base, &desc.base_color_texture) {
+            (Some(mask), Some(path)) if desc.emissive_texture.is_none() => {
+                let atlas = load_context
+                    .loader()
+                    .immediate()
+                    .load::<Image>(path.clone())
+                    .await?;
+                let emissive = mask.generate(atlas.get())?;
+                Some(load_context.add_labeled_asset("emissive".into(), emissive))
+            }
+            _ => None,
+        };
+        let mut load = |path: &Option<String>| {
+            path.as_ref()
+                .map(|path| load_context.load::<Image>(path.clone()))
+        };
+        let base = StandardMaterial {
+            base_color_texture: load(&desc.base_color_texture),
+            emissive: LinearRgba::from_vec3(Vec3::from_array(desc.emissive)),
+            emissive_texture: load(&desc.emissive_texture).or(generated_emissive),
+            reflectance: desc.reflectance,
+            ..default()
+        };
+        let mut extension = FakeInteriorMaterial {
+            occupant_texture: load(&desc.occupant_texture),
+            ..FakeInteriorMaterial::from(desc.params())
+        };
+        if let Some(depth_world) = desc.depth_world {
+            extension.depth_world = depth_world;
+            extension.use_depth_world = 1;
+        }
+        Ok(StandardFakeInteriorMaterial { base, extension })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["fakeinterior.ron"]
+    }
+}
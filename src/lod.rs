@@ -0,0 +1,234 @@
+//! Flat interiors for walls far from or turned away from the camera.
+//!
+//! Only the walls in front of the camera need the ray traced rooms. With a
+//! [`FakeInteriorLodCamera`], the other walls are switched to a copy of their material with
+//! [`QualityTier::Low`] (flat rooms), so they stay batched with each other and need no
+//! per-entity data in the shader. The copies follow changes of the detailed materials.
+//!
+//! There is one copy per detailed material, not per wall: all the flat walls of a material
+//! share it and its bind group. A per-entity flag would need the `instance_params` storage
+//! buffer, which WebGL2 doesn't have, and its whole table would be rebuilt and re-uploaded
+//! whenever a wall switched as the camera moves.
+
+use bevy::{math::Vec3A, prelude::*, utils::HashMap};
+
+use crate::{QualityTier, StandardFakeInteriorMaterial};
+
+/// Render the fake interiors of walls beyond `max_distance` from this camera, or seen at an
+/// angle above `max_angle`, as flat rooms.
+///
+/// The angle is measured from the direction the wall faces, its local `+Z` like
+/// [`fake_interior_wall_mesh`](crate::fake_interior_wall_mesh), or the
+/// [`FakeInteriorLodFacing`] of the entity, e.g. `Dir3::Y` for `Plane3d::default()` meshes.
+///
+/// Applies to every entity with a `StandardFakeInteriorMaterial`, by swapping its material
+/// handle for a [`QualityTier::Low`] copy, which also sends another
+/// [`FakeInteriorReady`](crate::FakeInteriorReady). With several of these cameras a wall is
+/// detailed if it is for any of them, without them every wall is detailed.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_fake_interior::FakeInteriorLodCamera;
+/// let lod = FakeInteriorLodCamera {
+///     max_distance: 50.0,
+///     max_angle: 60f32.to_radians(),
+/// };
+/// // a wall at the origin facing +Z.
+/// let wall = GlobalTransform::IDENTITY;
+/// assert!(!lod.is_flat(Vec3::new(0.0, 0.0, 10.0), &wall));
+/// // too far.
+/// assert!(lod.is_flat(Vec3::new(0.0, 0.0, 80.0), &wall));
+/// // close, but seen at a grazing angle or from behind.
+/// assert!(lod.is_flat(Vec3::new(10.0, 0.0, 1.0), &wall));
+/// assert!(lod.is_flat(Vec3::new(0.0, 0.0, -10.0), &wall));
+/// ```
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct FakeInteriorLodCamera {
+    /// Distance from the camera to the wall's origin.
+    pub max_distance: f32,
+    /// Angle in radians between the wall's facing (its local `+Z`, or its
+    /// [`FakeInteriorLodFacing`]) and the direction to the camera.
+    pub max_angle: f32,
+}
+
+impl Default for FakeInteriorLodCamera {
+    fn default() -> Self {
+        Self {
+            max_distance: 100.0,
+            max_angle: 75f32.to_radians(),
+        }
+    }
+}
+
+/// The local direction the wall of this entity faces for [`FakeInteriorLodCamera::max_angle`],
+/// for meshes not facing `+Z` like [`fake_interior_wall_mesh`](crate::fake_interior_wall_mesh).
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct FakeInteriorLodFacing(pub Dir3);
+
+impl Default for FakeInteriorLodFacing {
+    fn default() -> Self {
+        Self(Dir3::Z)
+    }
+}
+
+impl FakeInteriorLodCamera {
+    /// Whether the wall with the transform `wall`, facing its local `+Z`, is flat for a camera
+    /// at `camera`.
+    pub fn is_flat(&self, camera: Vec3, wall: &GlobalTransform) -> bool {
+        self.is_flat_facing(camera, wall, Dir3::Z)
+    }
+
+    /// Whether the wall with the transform `wall`, facing its local `facing` direction, is flat
+    /// for a camera at `camera`.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_fake_interior::FakeInteriorLodCamera;
+    /// let lod = FakeInteriorLodCamera::default();
+    /// // a `Plane3d::default()` wall facing +Y, seen from above.
+    /// let wall = GlobalTransform::IDENTITY;
+    /// let camera = Vec3::new(0.0, 10.0, 1.0);
+    /// assert!(!lod.is_flat_facing(camera, &wall, Dir3::Y));
+    /// assert!(lod.is_flat(camera, &wall));
+    /// ```
+    pub fn is_flat_facing(&self, camera: Vec3, wall: &GlobalTransform, facing: Dir3) -> bool {
+        let to_camera = camera - wall.translation();
+        let distance = to_camera.length();
+        if distance > self.max_distance {
+            return true;
+        }
+        // the camera is on the wall.
+        if distance == 0.0 {
+            return false;
+        }
+        // like a normal, so non-uniform scales don't tilt it.
+        let normal = wall.affine().matrix3.inverse().transpose() * Vec3A::from(*facing);
+        let facing = Vec3::from(normal.normalize()).dot(to_camera / distance);
+        facing.clamp(-1.0, 1.0).acos() > self.max_angle
+    }
+}
+
+/// The detailed material of a wall switched by [`FakeInteriorLodCamera`].
+#[derive(Component)]
+struct DetailedInterior(Handle<StandardFakeInteriorMaterial>);
+
+/// `QualityTier::Low` copies of the detailed materials.
+#[derive(Resource, Default)]
+struct FlatMaterials(
+    HashMap<AssetId<StandardFakeInteriorMaterial>, Handle<StandardFakeInteriorMaterial>>,
+);
+
+pub(crate) fn init_lod(app: &mut App) {
+    app.init_resource::<FlatMaterials>()
+        .register_type::<FakeInteriorLodCamera>()
+        .register_type::<FakeInteriorLodFacing>()
+        .add_systems(
+            PostUpdate,
+            (update_flat_materials, update_lods)
+                .chain()
+                .after(TransformSystem::TransformPropagate),
+        );
+}
+
+fn flat_copy(material: &StandardFakeInteriorMaterial) -> StandardFakeInteriorMaterial {
+    let mut flat = material.clone();
+    flat.extension.quality = QualityTier::Low;
+    flat
+}
+
+/// Copy changes of the detailed materials to their flat copies.
+fn update_flat_materials(
+    mut events: EventReader<AssetEvent<StandardFakeInteriorMaterial>>,
+    mut flat_materials: ResMut<FlatMaterials>,
+    mut materials: ResMut<Assets<StandardFakeInteriorMaterial>>,
+) {
+    for event in events.read() {
+        match *event {
+            AssetEvent::Modified { id } => {
+                if let Some(flat) = flat_materials.0.get(&id) {
+                    if let Some(material) = materials.get(id) {
+                        let copy = flat_copy(material);
+                        materials.insert(flat, copy);
+                    }
+                }
+            }
+            AssetEvent::Removed { id } => {
+                flat_materials.0.remove(&id);
+            }
+            _ => {}
+        }
+    }
+}
+
+type LodWall<'a> = (
+    Entity,
+    &'a mut MeshMaterial3d<StandardFakeInteriorMaterial>,
+    &'a GlobalTransform,
+    Option<&'a DetailedInterior>,
+    Option<&'a FakeInteriorLodFacing>,
+);
+
+fn update_lods(
+    mut commands: Commands,
+    cameras: Query<(&FakeInteriorLodCamera, &GlobalTransform)>,
+    mut walls: Query<LodWall>,
+    mut flat_materials: ResMut<FlatMaterials>,
+    mut materials: ResMut<Assets<StandardFakeInteriorMaterial>>,
+) {
+    // without cameras every wall is detailed, give the switched ones their material back.
+    if cameras.is_empty() {
+        for (entity, mut material, _, detailed, _) in &mut walls {
+            let Some(DetailedInterior(detailed)) = detailed else {
+                continue;
+            };
+            if flat_materials.0.get(&detailed.id()) == Some(&material.0) {
+                material.0 = detailed.clone();
+            }
+            commands.entity(entity).remove::<DetailedInterior>();
+        }
+        return;
+    }
+    for (entity, mut material, transform, detailed, facing) in &mut walls {
+        // a material set since the last switch is the new detailed one.
+        let detailed = match detailed {
+            Some(DetailedInterior(detailed))
+                if material.0 == *detailed
+                    || flat_materials.0.get(&detailed.id()) == Some(&material.0) =>
+            {
+                detailed.clone()
+            }
+            _ => {
+                commands
+                    .entity(entity)
+                    .insert(DetailedInterior(material.0.clone()));
+                material.0.clone()
+            }
+        };
+
+        let facing = facing.copied().unwrap_or_default().0;
+        let flat = cameras
+            .iter()
+            .all(|(lod, camera)| lod.is_flat_facing(camera.translation(), transform, facing));
+        let wanted = if !flat {
+            detailed
+        } else if let Some(copy) = flat_materials.0.get(&detailed.id()) {
+            copy.clone()
+        } else {
+            match materials.get(&detailed) {
+                None => continue,
+                Some(source) if source.extension.quality == QualityTier::Low => detailed,
+                Some(source) => {
+                    let copy = flat_copy(source);
+                    let copy = materials.add(copy);
+                    flat_materials.0.insert(detailed.id(), copy.clone());
+                    copy
+                }
+            }
+        };
+        if material.0 != wanted {
+            material.0 = wanted;
+        }
+    }
+}
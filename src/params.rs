@@ -0,0 +1,90 @@
+//! The numeric parameters of a [`FakeInteriorMaterial`], e.g. for building style presets.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::FakeInteriorMaterial;
+
+/// The numeric fields of a [`FakeInteriorMaterial`], without its textures and handles.
+///
+/// It implements `Serialize` and `Deserialize`, missing fields default to the
+/// [`FakeInteriorMaterial`] defaults. A [`FakeInteriorDescription`](crate::FakeInteriorDescription)
+/// holds the same fields for the `.fakeinterior.ron` loader.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_fake_interior::*;
+/// let style = FakeInteriorParams {
+///     rooms: Vec2::new(8.0, 4.0),
+///     depth: 0.3,
+///     ..default()
+/// };
+/// let mut material = FakeInteriorMaterial::from(style);
+/// assert_eq!(material.depth, 0.3);
+///
+/// // keeps the other fields of the material.
+/// material.emissive_layer = 0.5;
+/// FakeInteriorParams { depth: 0.6, ..style }.apply(&mut material);
+/// assert_eq!(material.depth, 0.6);
+/// assert_eq!(material.emissive_layer, 0.5);
+/// assert_eq!(FakeInteriorParams::from(&material).rooms, Vec2::new(8.0, 4.0));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FakeInteriorParams {
+    pub atlas_rooms: Vec2,
+    pub rooms: Vec2,
+    pub depth: f32,
+    pub room_seed: f32,
+    pub emission_seed: f32,
+    pub flicker_seed: f32,
+    pub emission_threshold: f32,
+    pub emission_intensity: f32,
+    pub uv_tiling: Vec2,
+}
+
+impl Default for FakeInteriorParams {
+    fn default() -> Self {
+        Self::from(&FakeInteriorMaterial::default())
+    }
+}
+
+impl FakeInteriorParams {
+    /// Set these fields of `material`, keeping its others.
+    pub fn apply(&self, material: &mut FakeInteriorMaterial) {
+        material.atlas_rooms = self.atlas_rooms;
+        material.rooms = self.rooms;
+        material.depth = self.depth;
+        material.room_seed = self.room_seed;
+        material.emission_seed = self.emission_seed;
+        material.flicker_seed = self.flicker_seed;
+        material.emission_threshold = self.emission_threshold;
+        material.emission_intensity = self.emission_intensity;
+        material.uv_tiling = self.uv_tiling;
+    }
+}
+
+impl From<&FakeInteriorMaterial> for FakeInteriorParams {
+    fn from(material: &FakeInteriorMaterial) -> Self {
+        Self {
+            atlas_rooms: material.atlas_rooms,
+            rooms: material.rooms,
+            depth: material.depth,
+            room_seed: material.room_seed,
+            emission_seed: material.emission_seed,
+            flicker_seed: material.flicker_seed,
+            emission_threshold: material.emission_threshold,
+            emission_intensity: material.emission_intensity,
+            uv_tiling: material.uv_tiling,
+        }
+    }
+}
+
+impl From<FakeInteriorParams> for FakeInteriorMaterial {
+    /// A material with these fields, the others are the defaults.
+    fn from(params: FakeInteriorParams) -> Self {
+        let mut material = Self::default();
+        params.apply(&mut material);
+        material
+    }
+}
@@ -0,0 +1,94 @@
+//! Placeholder room atlas for materials without a base color texture.
+//!
+//! A fake interior without a room atlas renders a flat color, which looks like nothing
+//! happened, e.g. for a material freshly added in an inspector. With
+//! [`FakeInteriorMaterialPlugin::use_placeholder`](crate::FakeInteriorMaterialPlugin::use_placeholder)
+//! such materials get [`FAKE_INTERIOR_PLACEHOLDER_HANDLE`], a checkered room built into the
+//! crate.
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+use crate::StandardFakeInteriorMaterial;
+
+/// Single room atlas set as the `base_color_texture` of materials without one.
+pub const FAKE_INTERIOR_PLACEHOLDER_HANDLE: Handle<Image> =
+    Handle::weak_from_u128(0x2b7e_91c4_0d3f_4a62_8c15_e9a0_57d8_c413);
+
+/// Size of the placeholder atlas.
+const SIZE: u32 = 64;
+/// Checker squares across the atlas.
+const CHECKERS: u32 = 8;
+
+/// A room seen through the window for the default `depth`: the back wall in the middle
+/// half, the side walls, ceiling (top) and floor (bottom) around it. Each face has its own
+/// checker colors, the alpha is opaque so the material's `depth` is used.
+fn placeholder_atlas() -> Image {
+    let data = (0..SIZE * SIZE)
+        .flat_map(|i| {
+            let texel = UVec2::new(i % SIZE, i / SIZE);
+            let d = (texel.as_vec2() + 0.5) / SIZE as f32 * 2.0 - 1.0;
+            let face = if d.abs().max_element() < 0.5 {
+                [200, 200, 200]
+            } else if d.x.abs() > d.y.abs() {
+                [150, 160, 200]
+            } else if d.y > 0.0 {
+                [190, 150, 110]
+            } else {
+                [230, 230, 210]
+            };
+            let checker = texel / (SIZE / CHECKERS);
+            let shade = if (checker.x + checker.y) & 1 == 0 {
+                1.0
+            } else {
+                0.6
+            };
+            let [r, g, b] = face.map(|c| (c as f32 * shade) as u8);
+            [r, g, b, 255]
+        })
+        .collect();
+    Image::new(
+        Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+pub(crate) fn init_placeholder(app: &mut App) {
+    app.world_mut()
+        .resource_mut::<Assets<Image>>()
+        .insert(FAKE_INTERIOR_PLACEHOLDER_HANDLE.id(), placeholder_atlas());
+    app.add_systems(Update, apply_placeholder);
+}
+
+/// Set the placeholder atlas on added or changed materials without a base color texture.
+fn apply_placeholder(
+    mut events: EventReader<AssetEvent<StandardFakeInteriorMaterial>>,
+    mut materials: ResMut<Assets<StandardFakeInteriorMaterial>>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = *event else {
+            continue;
+        };
+        // only take the material mutably when needed, that's a `Modified` event.
+        let missing = materials
+            .get(id)
+            .is_some_and(|material| material.base.base_color_texture.is_none());
+        if missing {
+            if let Some(material) = materials.get_mut(id) {
+                material.base.base_color_texture = Some(FAKE_INTERIOR_PLACEHOLDER_HANDLE);
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Coarse quality tiers of the interior shader.
+
+use bevy::prelude::*;
+
+/// Quality of the interior, one dial for scaling to weaker platforms, see
+/// [`FakeInteriorMaterial::quality`](crate::FakeInteriorMaterial::quality).
+///
+/// Each tier is its own pipeline, selected with shader defs. Lit rooms, far windows,
+/// occupants and the other per-room features stay in every tier.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default, Debug, PartialEq, Hash)]
+pub enum QualityTier {
+    /// Flat rooms without parallax: each window shows its whole room image like a billboard,
+    /// without the `Medium` details either (`FAKE_INTERIOR_FLAT` and
+    /// `FAKE_INTERIOR_NO_DETAILS` shader defs).
+    Low,
+    /// The ray traced room box with parallax, without the details: no box face shading
+    /// (`shade_box_faces`), frosted glass (`frost_amount`), chromatic aberration or glossy
+    /// floor (`FAKE_INTERIOR_NO_DETAILS` shader def).
+    Medium,
+    /// Everything the material enables.
+    #[default]
+    High,
+}
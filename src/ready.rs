@@ -0,0 +1,82 @@
+//! Event for loading screens: a fake interior entity's textures are loaded.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::StandardFakeInteriorMaterial;
+
+/// Sent once the base color and emissive textures of the material of `entity` are loaded,
+/// e.g. to hide a loading spinner.
+///
+/// Sent again when the entity gets another material. Textures that failed to load count as
+/// loaded, the asset server logs their errors, while an entity whose material failed to
+/// load is never ready.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fake_interior::*;
+/// fn hide_spinner(mut ready: EventReader<FakeInteriorReady>) {
+///     for FakeInteriorReady { entity, .. } in ready.read() {
+///         info!("interior of {entity} is ready");
+///     }
+/// }
+/// ```
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FakeInteriorReady {
+    pub entity: Entity,
+    pub asset_id: AssetId<StandardFakeInteriorMaterial>,
+}
+
+pub(crate) fn init_ready(app: &mut App) {
+    app.add_event::<FakeInteriorReady>()
+        .add_systems(Update, send_ready_events);
+}
+
+/// Whether the image is loaded or failed to load. Images added to the assets directly aren't
+/// known to the asset server, they are loaded once they are in the assets.
+fn image_done(
+    asset_server: &AssetServer,
+    images: &Assets<Image>,
+    image: &Option<Handle<Image>>,
+) -> bool {
+    let Some(image) = image else {
+        return true;
+    };
+    match asset_server.get_load_state(image) {
+        Some(state) => state.is_loaded() || state.is_failed(),
+        None => images.contains(image),
+    }
+}
+
+fn send_ready_events(
+    mut pending: Local<HashMap<Entity, AssetId<StandardFakeInteriorMaterial>>>,
+    changed: Query<
+        (Entity, &MeshMaterial3d<StandardFakeInteriorMaterial>),
+        Changed<MeshMaterial3d<StandardFakeInteriorMaterial>>,
+    >,
+    mut removed: RemovedComponents<MeshMaterial3d<StandardFakeInteriorMaterial>>,
+    materials: Res<Assets<StandardFakeInteriorMaterial>>,
+    images: Res<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+    mut ready: EventWriter<FakeInteriorReady>,
+) {
+    for entity in removed.read() {
+        pending.remove(&entity);
+    }
+    pending.extend(
+        changed
+            .iter()
+            .map(|(entity, material)| (entity, material.id())),
+    );
+
+    pending.retain(|&entity, &mut asset_id| {
+        let Some(material) = materials.get(asset_id) else {
+            return true;
+        };
+        let done = image_done(&asset_server, &images, &material.base.base_color_texture)
+            && image_done(&asset_server, &images, &material.base.emissive_texture);
+        if done {
+            ready.send(FakeInteriorReady { entity, asset_id });
+        }
+        !done
+    });
+}
@@ -0,0 +1,170 @@
+//! Rooms supplied as separate images instead of a packed atlas.
+//!
+//! Bevy's materials can't bind arrays of textures, so the room images registered in
+//! [`RoomImageArrays`] are copied into the layers of one array texture, which
+//! [`FakeInteriorMaterial::room_images`](crate::FakeInteriorMaterial::room_images) binds.
+//! The array is rebuilt when rooms are added or removed, or one of their images changes.
+//! Array textures also work on WebGL2, no feature is needed.
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            Extent3d, TextureDimension, TextureViewDescriptor, TextureViewDimension,
+        },
+    },
+    utils::{HashMap, HashSet},
+};
+
+/// Room images of the array textures for
+/// [`FakeInteriorMaterial::room_images`](crate::FakeInteriorMaterial::room_images), also
+/// usable for the time of day layers of
+/// [`FakeInteriorMaterial::emissive_layers`](crate::FakeInteriorMaterial::emissive_layers).
+///
+/// The images of an array must have the same size and format and no mipmaps. The array is
+/// (re)built once all of them are loaded, until then the materials using it aren't drawn.
+/// WebGL2 supports at least 256 layers.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_fake_interior::*;
+/// fn setup(
+///     asset_server: Res<AssetServer>,
+///     mut images: ResMut<Assets<Image>>,
+///     mut arrays: ResMut<RoomImageArrays>,
+/// ) {
+///     let rooms = arrays.add(
+///         &mut images,
+///         vec![
+///             asset_server.load("rooms/office.png"),
+///             asset_server.load("rooms/kitchen.png"),
+///         ],
+///     );
+///     // later, e.g. from a mod.
+///     arrays.push(&rooms, asset_server.load("rooms/workshop.png"));
+///     let interior = FakeInteriorMaterial {
+///         room_images: Some(rooms),
+///         ..default()
+///     };
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct RoomImageArrays {
+    arrays: HashMap<AssetId<Image>, Vec<Handle<Image>>>,
+    /// Arrays to rebuild.
+    dirty: HashSet<AssetId<Image>>,
+}
+
+impl RoomImageArrays {
+    /// Reserve the handle of a new array texture of `rooms`.
+    pub fn add(&mut self, images: &mut Assets<Image>, rooms: Vec<Handle<Image>>) -> Handle<Image> {
+        let array = images.reserve_handle();
+        self.set(&array, rooms);
+        array
+    }
+
+    /// Replace the rooms of `array`.
+    pub fn set(&mut self, array: &Handle<Image>, rooms: Vec<Handle<Image>>) {
+        self.arrays.insert(array.id(), rooms);
+        self.dirty.insert(array.id());
+    }
+
+    /// Add a room to the end of `array`.
+    pub fn push(&mut self, array: &Handle<Image>, room: Handle<Image>) {
+        self.arrays.entry(array.id()).or_default().push(room);
+        self.dirty.insert(array.id());
+    }
+
+    /// Remove every layer of `room` from `array`.
+    pub fn remove(&mut self, array: &Handle<Image>, room: &Handle<Image>) {
+        if let Some(rooms) = self.arrays.get_mut(&array.id()) {
+            rooms.retain(|r| r.id() != room.id());
+            self.dirty.insert(array.id());
+        }
+    }
+
+    /// The rooms of `array`, in layer order.
+    pub fn rooms(&self, array: &Handle<Image>) -> &[Handle<Image>] {
+        self.arrays.get(&array.id()).map_or(&[], Vec::as_slice)
+    }
+}
+
+pub(crate) fn init_room_images(app: &mut App) {
+    app.init_resource::<RoomImageArrays>()
+        .add_systems(Update, build_room_arrays);
+}
+
+/// The array texture of `rooms`, `None` if one isn't loaded yet or they don't match.
+fn build_array(rooms: &[Handle<Image>], images: &Assets<Image>) -> Option<Image> {
+    let loaded: Vec<&Image> = rooms
+        .iter()
+        .map(|room| images.get(room))
+        .collect::<Option<_>>()?;
+    let Some(first) = loaded.first() else {
+        warn!("RoomImageArrays: an array needs at least one room");
+        return None;
+    };
+    let descriptor = &first.texture_descriptor;
+    let matching = loaded.iter().all(|image| {
+        image.texture_descriptor.size == descriptor.size
+            && image.texture_descriptor.format == descriptor.format
+            && image.texture_descriptor.mip_level_count == 1
+            && image.texture_descriptor.size.depth_or_array_layers == 1
+    });
+    if !matching {
+        warn!("RoomImageArrays: the rooms of an array must have the same size and format, without mipmaps");
+        return None;
+    }
+    let mut array = Image::new(
+        Extent3d {
+            depth_or_array_layers: loaded.len() as u32,
+            ..descriptor.size
+        },
+        TextureDimension::D2,
+        loaded
+            .iter()
+            .flat_map(|image| &image.data)
+            .copied()
+            .collect(),
+        descriptor.format,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    // a single layer would be viewed as a plain 2d texture.
+    array.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::D2Array),
+        ..default()
+    });
+    array.sampler = first.sampler.clone();
+    Some(array)
+}
+
+fn build_room_arrays(
+    mut arrays: ResMut<RoomImageArrays>,
+    mut events: EventReader<AssetEvent<Image>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let arrays = &mut *arrays;
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = *event
+        else {
+            continue;
+        };
+        let changed = arrays
+            .arrays
+            .iter()
+            .filter(|(_, rooms)| rooms.iter().any(|room| room.id() == id))
+            .map(|(array, _)| *array);
+        arrays.dirty.extend(changed);
+    }
+
+    // arrays with rooms still loading are built again by the rooms' load events.
+    for array in arrays.dirty.drain() {
+        let Some(rooms) = arrays.arrays.get(&array) else {
+            continue;
+        };
+        if let Some(image) = build_array(rooms, &images) {
+            images.insert(array, image);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Images updated every frame, e.g. from a video decoder, without rebuilding bind groups.
+//!
+//! Changing an `Image` asset uploads it as a new GPU texture, and the material bind groups
+//! still using the old texture only pick it up when the material is rebuilt too. Frames
+//! written through [`StreamedImages`] are instead copied into the existing GPU texture, the
+//! `Image` asset and the materials using it don't change.
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssets,
+        render_resource::{Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d, TextureAspect},
+        renderer::RenderQueue,
+        texture::GpuImage,
+        MainWorld, Render, RenderApp, RenderSet,
+    },
+    utils::HashMap,
+};
+
+/// Frames to copy into the GPU textures of images, applied once per frame.
+///
+/// A frame replaces the whole image: it's the same size and format as the `Image` and
+/// has its layout (rows of texels, no padding). Only the first mip level is written, so
+/// streamed images shouldn't have mipmaps. Frames of images that aren't on the GPU yet are
+/// dropped. The CPU side `Image` data isn't updated, keep it `RenderAssetUsages::RENDER_WORLD`
+/// only to save the memory.
+#[derive(Resource, Default)]
+pub struct StreamedImages {
+    frames: HashMap<AssetId<Image>, Vec<u8>>,
+}
+
+impl StreamedImages {
+    /// Set the next frame of `image`, replacing a frame that wasn't applied yet.
+    pub fn write(&mut self, image: impl Into<AssetId<Image>>, data: Vec<u8>) {
+        self.frames.insert(image.into(), data);
+    }
+}
+
+/// The frames taken from [`StreamedImages`] in the render world.
+#[derive(Resource, Default)]
+struct ExtractedFrames(HashMap<AssetId<Image>, Vec<u8>>);
+
+pub(crate) fn init_streamed_images(app: &mut App) {
+    app.init_resource::<StreamedImages>();
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    render_app
+        .init_resource::<ExtractedFrames>()
+        .add_systems(ExtractSchedule, extract_frames)
+        .add_systems(Render, write_frames.in_set(RenderSet::PrepareResources));
+}
+
+fn extract_frames(mut main_world: ResMut<MainWorld>, mut extracted: ResMut<ExtractedFrames>) {
+    let mut streamed = main_world.resource_mut::<StreamedImages>();
+    if !streamed.frames.is_empty() {
+        extracted.0 = std::mem::take(&mut streamed.frames);
+    }
+}
+
+fn write_frames(
+    mut extracted: ResMut<ExtractedFrames>,
+    images: Res<RenderAssets<GpuImage>>,
+    queue: Res<RenderQueue>,
+) {
+    for (id, data) in extracted.0.drain() {
+        let Some(image) = images.get(id) else {
+            continue;
+        };
+        let Some(texel_size) = image.texture_format.block_copy_size(None) else {
+            warn_once!(
+                "StreamedImages: can't write frames of {:?} images",
+                image.texture_format
+            );
+            continue;
+        };
+        let bytes_per_row = image.size.x * texel_size;
+        if data.len() != (bytes_per_row * image.size.y) as usize {
+            warn_once!(
+                "StreamedImages: frame of {} bytes doesn't match its {}x{} image",
+                data.len(),
+                image.size.x,
+                image.size.y
+            );
+            continue;
+        }
+        queue.write_texture(
+            ImageCopyTexture {
+                texture: &image.texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            &data,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: None,
+            },
+            Extent3d {
+                width: image.size.x,
+                height: image.size.y,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
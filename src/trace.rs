@@ -0,0 +1,546 @@
+//! CPU version of the interior ray trace of `fake_interior.wgsl`.
+
+use bevy::{math::Vec3A, prelude::*};
+
+use crate::FakeInteriorMaterial;
+
+// Shading of the room box faces for `shade_box_faces`, keep in sync with `fake_interior.wgsl`.
+const SIDE_WALL_SHADE: f32 = 0.8;
+const CEILING_SHADE: f32 = 0.9;
+const FLOOR_SHADE: f32 = 0.7;
+
+// Smallest slope of the view ray into the room and largest `near_clip`, keep in sync with
+// `fake_interior.wgsl`.
+const MIN_VIEW_SLOPE: f32 = 0.001;
+const MAX_NEAR_CLIP: f32 = 0.99;
+
+/// `43758.5453` of the shader's random functions, as an `f32`.
+const RANDOM_SCALE: f32 = 43758.547;
+
+/// WGSL's `fract`, `x - floor(x)` also for negative `x`.
+fn fract(x: f32) -> f32 {
+    x - x.floor()
+}
+
+/// `random1D` of the shader.
+fn random1d(s: f32) -> f32 {
+    fract((s * 12.9898).sin() * RANDOM_SCALE)
+}
+
+/// `random2D` of the shader.
+fn random2d(s: f32) -> Vec2 {
+    Vec2::new(
+        fract((s * 12.9898).sin() * RANDOM_SCALE),
+        fract((s * 78.233).sin() * RANDOM_SCALE),
+    )
+}
+
+/// Room box in room cell space, its x/y faces are scaled by `back_scale` at `max.z`.
+struct RoomBox {
+    min: Vec3,
+    max: Vec3,
+    back_scale: Vec2,
+}
+
+impl RoomBox {
+    /// x/y scale of the room's side faces at depth `z`.
+    fn scale_at(&self, z: f32) -> Vec2 {
+        Vec2::ONE.lerp(
+            self.back_scale,
+            (z - self.min.z) / (self.max.z - self.min.z),
+        )
+    }
+
+    /// Per-axis distances along `dir` from `pos` to the room faces it's heading towards.
+    fn face_distances(&self, pos: Vec3, dir: Vec3) -> Vec3 {
+        let scale = self.scale_at(pos.z);
+        let slope = (self.back_scale - 1.0) / (self.max.z - self.min.z);
+        let k_x = slanted_faces_distance(
+            pos.x,
+            dir.x,
+            dir.z,
+            self.min.x * scale.x,
+            self.max.x * scale.x,
+            self.min.x * slope.x,
+            self.max.x * slope.x,
+        );
+        let k_y = slanted_faces_distance(
+            pos.y,
+            dir.y,
+            dir.z,
+            self.min.y * scale.y,
+            self.max.y * scale.y,
+            self.min.y * slope.y,
+            self.max.y * slope.y,
+        );
+        let face_z = if dir.z > 0.0 { self.max.z } else { self.min.z };
+        Vec3::new(k_x, k_y, (face_z - pos.z) / dir.z)
+    }
+
+    /// Move `pos` on the window along `dir` to the `near_clip` plane, kept inside the box.
+    fn clip_near(&self, pos: Vec3, dir: Vec3, near_clip: f32) -> Vec3 {
+        if near_clip <= 0.0 {
+            return pos;
+        }
+        let clip_z = self.min.z + (self.max.z - self.min.z) * near_clip.min(MAX_NEAR_CLIP);
+        let pos = pos + (clip_z - pos.z) / dir.z * dir;
+        let scale = self.scale_at(clip_z);
+        pos.xy()
+            .clamp(self.min.xy() * scale, self.max.xy() * scale)
+            .extend(clip_z)
+    }
+
+    /// Map a point in the room to the unit room box the atlas was rendered for.
+    fn to_unit(&self, pos: Vec3) -> Vec3 {
+        let scale = self.scale_at(pos.z);
+        let face_min = (self.min.xy() * scale).extend(self.min.z);
+        let face_max = (self.max.xy() * scale).extend(self.max.z);
+        (pos - face_min) / (face_max - face_min) * 2.0 - 1.0
+    }
+}
+
+/// Distance along `d` from `p` to the nearest of a min and max face moving
+/// `slope_min`/`slope_max` per unit of z. Only faces the ray is approaching count.
+fn slanted_faces_distance(
+    p: f32,
+    d: f32,
+    dz: f32,
+    face_min: f32,
+    face_max: f32,
+    slope_min: f32,
+    slope_max: f32,
+) -> f32 {
+    let v_min = d - slope_min * dz;
+    let v_max = d - slope_max * dz;
+    let mut k = 1e30_f32;
+    if v_max > 0.0 {
+        k = k.min((face_max - p) / v_max);
+    }
+    if v_min < 0.0 {
+        k = k.min((face_min - p) / v_min);
+    }
+    k
+}
+
+/// Keep the ray `dir` heading into the room, even along the wall.
+fn into_room(dir: Vec3) -> Vec3 {
+    dir.with_z(dir.z.max((dir.xy().length() * MIN_VIEW_SLOPE).max(1e-8)))
+}
+
+/// `rotate_interior` of the shader, turn `dir` by `angle` around the room's up axis (y).
+fn rotate_interior(dir: Vec3, angle: f32) -> Vec3 {
+    let (s, c) = angle.sin_cos();
+    Vec3::new(dir.x * c + dir.z * s, dir.y, dir.z * c - dir.x * s)
+}
+
+/// Room UV of a hit in the unit room box.
+fn room_uv_from_hit(hit: Vec3, far_frac: f32, depth_scale: f32) -> Vec2 {
+    // 0.0 - 1.0 room depth
+    let interp = hit.z * 0.5 + 0.5;
+    // account for perspective in "room" textures
+    let real_z = interp.clamp(0.0, 1.0) / depth_scale + 1.0;
+    let interp = (1.0 - 1.0 / real_z) * (depth_scale + 1.0);
+    hit.xy() * (1.0 + (far_frac - 1.0) * interp) * 0.5 + 0.5
+}
+
+/// Which face of the room box a ray hit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RoomFace {
+    Side,
+    Ceiling,
+    /// +y in the atlas.
+    Floor,
+    Back,
+}
+
+/// An interior ray hit, see [`FakeInteriorMaterial::interior_hit`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct InteriorHit {
+    pub face: RoomFace,
+    /// UV of the hit in its room of the atlas.
+    pub room_uv: Vec2,
+    /// UV of the hit in the atlas.
+    pub atlas_uv: Vec2,
+}
+
+impl FakeInteriorMaterial {
+    /// Mirroring of the room `cell` for `mirror_tiling`, every other cell along each axis so
+    /// neighbours share their walls.
+    fn cell_mirror(&self, cell: Vec2) -> Vec2 {
+        if self.mirror_tiling == 0 {
+            return Vec2::ONE;
+        }
+        Vec2::select(
+            (cell * 0.5).fract_gl().cmpge(Vec2::splat(0.5)),
+            -Vec2::ONE,
+            Vec2::ONE,
+        )
+    }
+
+    /// The room box of the room `cell` in room cell space.
+    fn room_box(&self, cell: Vec2, rooms: Vec2, cell_mirror: Vec2) -> RoomBox {
+        let room_aspect = self.room_aspect / self.room_aspect.min_element();
+        let mut room = RoomBox {
+            min: self.box_min * room_aspect.extend(1.0),
+            max: self.box_max * room_aspect.extend(1.0),
+            back_scale: self.back_wall_scale,
+        };
+        // the rooms on the surface edges end at the edge instead of leaking past a corner.
+        if self.clip_at_edges != 0 {
+            let at_min = cell.cmple(Vec2::ZERO);
+            let at_max = cell.cmpge(rooms.ceil() - 1.0);
+            let mirrored = cell_mirror.cmplt(Vec2::ZERO);
+            let clip_min = (at_min & !mirrored) | (at_max & mirrored);
+            let clip_max = (at_max & !mirrored) | (at_min & mirrored);
+            let min = Vec2::select(clip_min, room.min.xy().max(-Vec2::ONE), room.min.xy());
+            let max = Vec2::select(clip_max, room.max.xy().min(Vec2::ONE), room.max.xy());
+            room.min = min.extend(room.min.z);
+            room.max = max.extend(room.max.z);
+        }
+        room
+    }
+
+    /// Trace the interior seen through surface `uv` along the tangent space `view_dir`,
+    /// like the shader. `atlas` supplies the room depths in its alpha, `index_map` is the
+    /// loaded `room_index_map`.
+    ///
+    /// Assumes square room cells, `depth_world` and the per-entity parameters
+    /// (`rooms_jitter`, instance overrides) aren't supported.
+    pub(crate) fn interior_hit(
+        &self,
+        uv: Vec2,
+        view_dir: Vec3,
+        atlas: &Image,
+        index_map: Option<&Image>,
+    ) -> InteriorHit {
+        let rooms = self.shader_rooms();
+        let atlas_rooms = self.atlas_rooms;
+        let surface_uv = uv / self.uv_tiling;
+        let grid_uv = surface_uv * rooms;
+        let room_uv = grid_uv.fract_gl();
+        let mut room_index_uv = grid_uv.floor();
+        let room_index = room_index_uv.x + room_index_uv.y * rooms.x;
+        let room_seed = room_index * self.room_seed;
+        let cell_mirror = self.cell_mirror(room_index_uv);
+
+        // randomize the rooms, unless the index map picks the room of this cell.
+        let picked = index_map.and_then(|map| {
+            let size = map.size().as_vec2();
+            let texel = room_index_uv.rem_euclid(size).as_uvec2();
+            let color = map.get_color_at(texel.x, texel.y).ok()?.to_linear();
+            (color.alpha >= 0.5).then(|| (color.red * 255.0).round())
+        });
+        room_index_uv += match picked {
+            Some(k) => Vec2::new(k % atlas_rooms.x, (k / atlas_rooms.x).floor()) - room_index_uv,
+            None => (random2d(room_seed) * atlas_rooms).floor(),
+        };
+
+        // room depth from the atlas alpha, else `depth`.
+        let depth_uv = ((room_index_uv + 0.5) / atlas_rooms).fract_gl();
+        let mut far_frac = 1.0;
+        if self.night_only == 0 && self.emissive_from_base_alpha == 0 {
+            far_frac = atlas_texel(atlas, depth_uv).map_or(1.0, |texel| texel.alpha());
+        }
+        if far_frac >= 0.99 {
+            far_frac = self.depth;
+            if self.depth_variation > 0.0 {
+                let variation = (random1d(room_seed + 61.0) * 2.0 - 1.0) * self.depth_variation;
+                far_frac = (self.depth + variation).clamp(0.0, 0.99);
+            }
+            if !(0.0..1.0).contains(&far_frac) {
+                far_frac = 0.5;
+            }
+        }
+        // a flat decal for `depth = 0.0`, straight to the back wall.
+        let flat_room = far_frac <= 0.0;
+        let depth_scale = if flat_room {
+            1.0
+        } else {
+            1.0 / (1.0 - far_frac) - 1.0
+        };
+
+        let room = self.room_box(grid_uv.floor(), rooms, cell_mirror);
+        let mut pos = ((room_uv * 2.0 - 1.0) * cell_mirror).extend(room.min.z);
+        let view_dir = rotate_interior(view_dir, self.interior_rotation);
+        let dir = if flat_room {
+            Vec3::Z
+        } else {
+            into_room((view_dir.xy() * cell_mirror).extend(-view_dir.z * depth_scale))
+        };
+        let flat_uv = pos.xy() * 0.5 + 0.5;
+        if !flat_room {
+            pos = room.clip_near(pos, dir, self.near_clip);
+        }
+        let k = room.face_distances(pos, dir);
+        let k_min = k.min_element();
+        pos += k_min * dir;
+
+        let hit = room.to_unit(pos);
+        let interior_uv = if flat_room {
+            flat_uv
+        } else {
+            room_uv_from_hit(hit, far_frac, depth_scale)
+        };
+        let face = if k.x == k_min {
+            RoomFace::Side
+        } else if k.y == k_min && dir.y > 0.0 {
+            RoomFace::Floor
+        } else if k.y == k_min {
+            RoomFace::Ceiling
+        } else {
+            RoomFace::Back
+        };
+        InteriorHit {
+            face,
+            room_uv: interior_uv,
+            atlas_uv: ((room_index_uv + interior_uv) / atlas_rooms).fract_gl(),
+        }
+    }
+
+    /// Returns the unlit interior color seen through surface `uv`, like the shader without
+    /// lighting and glow.
+    ///
+    /// `view_dir` is the direction from the eye to the surface in its tangent space: x along
+    /// the tangent (U), y along the bitangent and z along the normal, so looking straight at
+    /// the wall is `-Vec3::Z`. Assumes square room cells and takes the nearest atlas texel.
+    /// `depth_world` and the per-entity parameters (`rooms_jitter`, instance overrides)
+    /// aren't supported.
+    ///
+    /// Returns `None` if the base color atlas isn't loaded or its data isn't kept on the
+    /// CPU, it needs `RenderAssetUsages::MAIN_WORLD` and an uncompressed format.
+    ///
+    /// ```
+    /// # use bevy::{prelude::*, render::{render_asset::RenderAssetUsages, render_resource::*}};
+    /// # use bevy_fake_interior::FakeInteriorMaterial;
+    /// // a single room, red on the left and blue on the right.
+    /// let atlas = Image::new(
+    ///     Extent3d { width: 2, height: 1, depth_or_array_layers: 1 },
+    ///     TextureDimension::D2,
+    ///     vec![255, 0, 0, 255, 0, 0, 255, 255],
+    ///     TextureFormat::Rgba8UnormSrgb,
+    ///     RenderAssetUsages::MAIN_WORLD,
+    /// );
+    /// let mut images = Assets::<Image>::default();
+    /// let base = StandardMaterial {
+    ///     base_color_texture: Some(images.add(atlas)),
+    ///     ..default()
+    /// };
+    /// let interior = FakeInteriorMaterial::default();
+    ///
+    /// let left = interior.sample_interior(&base, Vec2::new(0.25, 0.5), -Vec3::Z, &images);
+    /// assert_eq!(left, Some(Color::linear_rgb(1.0, 0.0, 0.0)));
+    /// let right = interior.sample_interior(&base, Vec2::new(0.75, 0.5), -Vec3::Z, &images);
+    /// assert_eq!(right, Some(Color::linear_rgb(0.0, 0.0, 1.0)));
+    /// // looking to the right from the left half of the window sees the right wall.
+    /// let to_right = Vec3::new(1.0, 0.0, -0.2);
+    /// let angled = interior.sample_interior(&base, Vec2::new(0.25, 0.5), to_right, &images);
+    /// assert_eq!(angled, Some(Color::linear_rgb(0.0, 0.0, 1.0)));
+    ///
+    /// // `depth` 0 is a flat decal, the same room texel from any angle.
+    /// let flat = FakeInteriorMaterial {
+    ///     depth: 0.0,
+    ///     ..default()
+    /// };
+    /// let decal = flat.sample_interior(&base, Vec2::new(0.25, 0.5), to_right, &images);
+    /// assert_eq!(decal, left);
+    ///
+    /// // two `color_levels`: the tinted red snaps back to full red.
+    /// let tinted = StandardMaterial {
+    ///     base_color: Color::srgb(0.6, 0.6, 0.6),
+    ///     ..base.clone()
+    /// };
+    /// let retro = FakeInteriorMaterial {
+    ///     color_levels: 2,
+    ///     ..default()
+    /// };
+    /// let dimmed = interior.sample_interior(&tinted, Vec2::new(0.25, 0.5), -Vec3::Z, &images);
+    /// assert_ne!(dimmed, left);
+    /// let snapped = retro.sample_interior(&tinted, Vec2::new(0.25, 0.5), -Vec3::Z, &images);
+    /// assert_eq!(snapped, left);
+    ///
+    /// // the atlas as two rooms, an index map shows the second one (blue) in every cell.
+    /// let index_map = Image::new(
+    ///     Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+    ///     TextureDimension::D2,
+    ///     vec![1, 0, 0, 255],
+    ///     TextureFormat::Rgba8Unorm,
+    ///     RenderAssetUsages::MAIN_WORLD,
+    /// );
+    /// let two_rooms = FakeInteriorMaterial {
+    ///     atlas_rooms: Vec2::new(2.0, 1.0),
+    ///     room_index_map: Some(images.add(index_map)),
+    ///     ..default()
+    /// };
+    /// let picked = two_rooms.sample_interior(&base, Vec2::new(0.5, 0.5), -Vec3::Z, &images);
+    /// assert_eq!(picked, Some(Color::linear_rgb(0.0, 0.0, 1.0)));
+    ///
+    /// // no atlas, nothing to sample.
+    /// let untextured = StandardMaterial::default();
+    /// assert_eq!(interior.sample_interior(&untextured, Vec2::ZERO, -Vec3::Z, &images), None);
+    /// ```
+    pub fn sample_interior(
+        &self,
+        base: &StandardMaterial,
+        uv: Vec2,
+        view_dir: Vec3,
+        images: &Assets<Image>,
+    ) -> Option<Color> {
+        let atlas = images.get(base.base_color_texture.as_ref()?)?;
+        let index_map = self.room_index_map.as_ref().and_then(|map| images.get(map));
+        let hit = self.interior_hit(uv, view_dir, atlas, index_map);
+        let mut color = atlas_texel(atlas, hit.atlas_uv)?;
+        if self.base_color_is_linear != 0 {
+            // the shader undoes the sRGB decode, the stored values are linear.
+            let srgba = color.to_srgba();
+            color = LinearRgba::new(srgba.red, srgba.green, srgba.blue, srgba.alpha).into();
+        }
+        let shade = match hit.face {
+            _ if self.shade_box_faces == 0 => 1.0,
+            RoomFace::Side => SIDE_WALL_SHADE,
+            RoomFace::Floor => FLOOR_SHADE,
+            RoomFace::Ceiling => CEILING_SHADE,
+            RoomFace::Back => 1.0,
+        };
+        // darken towards the room corners.
+        let vignette_dist = hit.room_uv * 2.0 - 1.0;
+        let vignette = 1.0 - self.room_vignette * vignette_dist.length_squared() * 0.5;
+        let tint = base.base_color.to_linear().to_vec3() * shade * vignette.clamp(0.0, 1.0);
+        let color = LinearRgba::from_vec3(color.to_linear().to_vec3() * tint);
+        if self.color_levels > 1 {
+            // even steps in sRGB, like the shader.
+            let steps = (self.color_levels - 1) as f32;
+            let srgb = Srgba::from(LinearRgba::from_vec3(
+                color.to_vec3().clamp(Vec3::ZERO, Vec3::ONE),
+            ));
+            let quantized = (srgb.to_vec3() * steps).round() / steps;
+            return Some(LinearRgba::from(Srgba::from_vec3(quantized)).into());
+        }
+        Some(color.into())
+    }
+
+    /// Returns the world space point of the room box that the eye sees through surface
+    /// `local_uv` along `view_dir`, like the shader, e.g. to place an entity at the back wall
+    /// of the room the player clicked.
+    ///
+    /// `transform` is the [`GlobalTransform`] of a 1x1
+    /// [`fake_interior_wall_mesh`](crate::fake_interior_wall_mesh) (facing `+Z`, U along `+X` and
+    /// V along `-Y`), include the size of other walls in it:
+    /// `transform * Transform::from_scale(size.extend(1.0))`. `view_dir` is the world space
+    /// direction from the eye to the surface and `depth` the depth of the rooms in world units,
+    /// as in `depth_world`. Per-entity parameters (`rooms_jitter`, instance overrides) aren't
+    /// supported.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_fake_interior::FakeInteriorMaterial;
+    /// let interior = FakeInteriorMaterial::default();
+    /// // a 4x4 wall with a single room, 4 units deep.
+    /// let wall = GlobalTransform::from_scale(Vec3::new(4.0, 4.0, 1.0));
+    ///
+    /// let back = interior.interior_hit_world(&wall, Vec2::splat(0.5), -Vec3::Z, 4.0);
+    /// assert!(back.abs_diff_eq(Vec3::new(0.0, 0.0, -4.0), 1e-5));
+    /// // looking to the right from the middle hits the right wall, 2 units away.
+    /// let side =
+    ///     interior.interior_hit_world(&wall, Vec2::splat(0.5), Vec3::new(1.0, 0.0, -1.0), 4.0);
+    /// assert!(side.abs_diff_eq(Vec3::new(2.0, 0.0, -2.0), 1e-5));
+    /// // along the wall the ray still enters the room, hitting the right wall by the window.
+    /// for grazing in [Vec3::X, Vec3::new(1.0, 0.0, -1e-6)] {
+    ///     let hit = interior.interior_hit_world(&wall, Vec2::splat(0.5), grazing, 4.0);
+    ///     assert!(hit.is_finite());
+    ///     assert!(hit.abs_diff_eq(Vec3::new(2.0, 0.0, 0.0), 1e-2));
+    /// }
+    /// // `near_clip` cuts the first quarter of the room off, the ray starts 1 unit in.
+    /// let clipped = FakeInteriorMaterial {
+    ///     near_clip: 0.25,
+    ///     ..default()
+    /// };
+    /// let hit = clipped.interior_hit_world(&wall, Vec2::splat(0.5), Vec3::X, 4.0);
+    /// assert!(hit.abs_diff_eq(Vec3::new(2.0, 0.0, -1.0), 1e-5));
+    /// let back = clipped.interior_hit_world(&wall, Vec2::splat(0.5), -Vec3::Z, 4.0);
+    /// assert!(back.abs_diff_eq(Vec3::new(0.0, 0.0, -4.0), 1e-5));
+    ///
+    /// // looking up from the top half of the window hits the ceiling, also on a mirrored wall
+    /// // (negative scale), where the shader flips the bitangent like this.
+    /// let up = Vec3::new(0.0, 1.0, -1.0);
+    /// let mirrored = GlobalTransform::from_scale(Vec3::new(-4.0, 4.0, 1.0));
+    /// for wall in [wall, mirrored] {
+    ///     let ceiling = interior.interior_hit_world(&wall, Vec2::new(0.5, 0.25), up, 4.0);
+    ///     assert!(ceiling.abs_diff_eq(Vec3::new(0.0, 2.0, -1.0), 1e-5));
+    /// }
+    ///
+    /// // the same wall moved and turned to face +X.
+    /// let wall = GlobalTransform::from(
+    ///     Transform::from_xyz(10.0, 0.0, 0.0)
+    ///         .with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2))
+    ///         .with_scale(Vec3::new(4.0, 4.0, 1.0)),
+    /// );
+    /// let back = interior.interior_hit_world(&wall, Vec2::splat(0.5), -Vec3::X, 4.0);
+    /// assert!(back.abs_diff_eq(Vec3::new(6.0, 0.0, 0.0), 1e-5));
+    /// ```
+    pub fn interior_hit_world(
+        &self,
+        transform: &GlobalTransform,
+        local_uv: Vec2,
+        view_dir: Vec3,
+        depth: f32,
+    ) -> Vec3 {
+        let affine = transform.affine();
+        let surface_pos =
+            transform.transform_point(Vec3::new(local_uv.x - 0.5, 0.5 - local_uv.y, 0.0));
+        let u_axis = affine.transform_vector3(Vec3::X);
+        let v_axis = affine.transform_vector3(Vec3::NEG_Y);
+        // the shader's orthonormal tangent frame.
+        let normal = Vec3::from(affine.matrix3.inverse().transpose() * Vec3A::Z).normalize();
+        let tangent = (u_axis - normal * normal.dot(u_axis)).normalize();
+        let mut bitangent = tangent.cross(normal);
+        // mirrored transforms, folded into the tangent's `w` the shader flips the bitangent by.
+        if affine.matrix3.determinant() < 0.0 {
+            bitangent = -bitangent;
+        }
+
+        let rooms = self.shader_rooms();
+        let grid_uv = local_uv / self.uv_tiling * rooms;
+        let room_uv = grid_uv.fract_gl();
+        let cell_mirror = self.cell_mirror(grid_uv.floor());
+        let room = self.room_box(grid_uv.floor(), rooms, cell_mirror);
+
+        // world size of a room cell, the room box spans 2 units along each axis.
+        let cell_size = Vec2::new(u_axis.length(), v_axis.length()) * self.uv_tiling / rooms;
+        let cell_width = 0.5 * (cell_size.x + cell_size.y);
+        let cell_aspect = cell_width / cell_size.max(Vec2::splat(0.0001));
+        let depth_scale = cell_width / depth.max(0.0001);
+        let surface_dir = rotate_interior(
+            Vec3::new(
+                view_dir.dot(tangent),
+                view_dir.dot(bitangent),
+                view_dir.dot(normal),
+            ),
+            self.interior_rotation,
+        );
+        let dir = into_room(
+            (surface_dir.xy() * cell_aspect * cell_mirror).extend(-surface_dir.z * depth_scale),
+        );
+        let start = ((room_uv * 2.0 - 1.0) * cell_mirror).extend(room.min.z);
+        let pos = room.clip_near(start, dir, self.near_clip);
+        let k_min = room.face_distances(pos, dir).min_element();
+        // back from room cell space, a room spans 2 units, to the surface's tangent space.
+        let offset = pos + k_min * dir - start;
+        let local = rotate_interior(
+            Vec3::new(
+                offset.x / (cell_aspect.x * cell_mirror.x),
+                offset.y / (cell_aspect.y * cell_mirror.y),
+                -offset.z / depth_scale,
+            ) * cell_width
+                * 0.5,
+            -self.interior_rotation,
+        );
+        surface_pos + tangent * local.x + bitangent * local.y + normal * local.z
+    }
+}
+
+/// Nearest texel of `atlas` at `uv`, `None` if the image data isn't on the CPU.
+fn atlas_texel(atlas: &Image, uv: Vec2) -> Option<Color> {
+    let size = atlas.size();
+    let texel = (uv * size.as_vec2()).as_uvec2().min(size - 1);
+    atlas.get_color_at(texel.x, texel.y).ok()
+}
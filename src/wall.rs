@@ -0,0 +1,34 @@
+//! Helpers to spawn ready to use fake interior walls.
+
+use bevy::prelude::*;
+
+use crate::StandardFakeInteriorMaterial;
+
+/// Plane mesh for a fake interior wall of `size`, facing `+Z` with generated tangents.
+pub fn fake_interior_wall_mesh(size: Vec2) -> Mesh {
+    Plane3d::new(Vec3::Z, size / 2.0)
+        .mesh()
+        .build()
+        .with_generated_tangents()
+        .expect("plane mesh has positions, normals and UVs")
+}
+
+/// Spawn a fake interior wall of `size` with `material`, facing `+Z` in its local space.
+///
+/// Adds a new mesh for each wall, spawn the same [`fake_interior_wall_mesh`] handle
+/// yourself for many walls of the same size.
+pub fn spawn_fake_interior_wall(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    material: Handle<StandardFakeInteriorMaterial>,
+    size: Vec2,
+    transform: Transform,
+) -> Entity {
+    commands
+        .spawn((
+            Mesh3d(meshes.add(fake_interior_wall_mesh(size))),
+            MeshMaterial3d(material),
+            transform,
+        ))
+        .id()
+}
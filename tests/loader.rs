@@ -0,0 +1,271 @@
+//! Loads `.fakeinterior.ron` files with the `FakeInteriorLoader` and materials with
+//! `FakeInteriorMaterial::from_paths`, and checks the placeholder atlas of materials without
+//! one and the `FakeInteriorReady` event. Doesn't need a GPU.
+
+use bevy::{
+    asset::{io::AssetSource, LoadState},
+    log::LogPlugin,
+    prelude::*,
+    render::{settings::WgpuSettings, RenderPlugin},
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+
+use bevy_fake_interior::*;
+
+const MAX_FRAMES: u32 = 1000;
+
+fn app() -> App {
+    // the loaded materials are checked as written in the files.
+    app_with(FakeInteriorMaterialPlugin {
+        use_placeholder: false,
+        ..default()
+    })
+}
+
+fn app_with(plugin: FakeInteriorMaterialPlugin) -> App {
+    let mut app = App::new();
+
+    app.register_asset_source(
+        "loader",
+        AssetSource::build().with_reader(AssetSource::get_default_reader("tests/loader".into())),
+    );
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            })
+            .disable::<WinitPlugin>()
+            .disable::<LogPlugin>(),
+    );
+    app.add_plugins(plugin);
+    app.finish();
+    app
+}
+
+/// Load `path` and update `app` until it's loaded or failed.
+fn load(app: &mut App, path: &str) -> (Handle<StandardFakeInteriorMaterial>, LoadState) {
+    let handle = app.world().resource::<AssetServer>().load(path.to_string());
+    for _ in 0..MAX_FRAMES {
+        app.update();
+        let state = app.world().resource::<AssetServer>().load_state(&handle);
+        if matches!(state, LoadState::Loaded | LoadState::Failed(_)) {
+            return (handle, state);
+        }
+    }
+    panic!("timed out loading {path}");
+}
+
+fn texture_path(texture: &Option<Handle<Image>>) -> Option<String> {
+    texture
+        .as_ref()
+        .and_then(|texture| texture.path())
+        .map(|path| path.to_string())
+}
+
+#[test]
+fn loads_description() {
+    let mut app = app();
+    let (handle, state) = load(&mut app, "interiors/rooms.fakeinterior.ron");
+    assert!(matches!(state, LoadState::Loaded), "{state:?}");
+
+    let materials = app
+        .world()
+        .resource::<Assets<StandardFakeInteriorMaterial>>();
+    let material = materials.get(&handle).unwrap();
+    assert_eq!(
+        texture_path(&material.base.base_color_texture).as_deref(),
+        Some("textures/rooms_depth.png")
+    );
+    assert_eq!(
+        texture_path(&material.base.emissive_texture).as_deref(),
+        Some("textures/rooms_emit.png")
+    );
+    assert_eq!(material.base.emissive, LinearRgba::rgb(1.0, 1.0, 1.0));
+    assert_eq!(material.base.reflectance, 1.0);
+
+    let interior = &material.extension;
+    assert_eq!(interior.atlas_rooms, Vec2::new(3.0, 2.0));
+    assert_eq!(interior.rooms, Vec2::new(6.0, 6.0));
+    assert_eq!(interior.depth, 0.5);
+    assert_eq!(interior.room_seed, 1.2);
+    assert_eq!(interior.emission_intensity, 10.0);
+    // not in the file
+    let defaults = FakeInteriorMaterial::default();
+    assert_eq!(interior.emission_threshold, defaults.emission_threshold);
+    assert_eq!(interior.use_depth_world, 0);
+    assert!(interior.occupant_texture.is_none());
+}
+
+#[test]
+fn depth_world_enables_world_depth() {
+    let mut app = app();
+    let (handle, state) = load(&mut app, "loader://depth_world.fakeinterior.ron");
+    assert!(matches!(state, LoadState::Loaded), "{state:?}");
+
+    let materials = app
+        .world()
+        .resource::<Assets<StandardFakeInteriorMaterial>>();
+    let material = materials.get(&handle).unwrap();
+    assert_eq!(material.extension.depth_world, 3.0);
+    assert_eq!(material.extension.use_depth_world, 1);
+    assert!(material.base.base_color_texture.is_none());
+}
+
+#[test]
+fn emissive_from_base_generates_lit_mask() {
+    let mut app = app();
+    let (handle, state) = load(&mut app, "loader://emissive_from_base.fakeinterior.ron");
+    assert!(matches!(state, LoadState::Loaded), "{state:?}");
+
+    let materials = app
+        .world()
+        .resource::<Assets<StandardFakeInteriorMaterial>>();
+    let emissive = materials
+        .get(&handle)
+        .unwrap()
+        .base
+        .emissive_texture
+        .clone();
+    assert_eq!(
+        texture_path(&emissive).as_deref(),
+        Some("loader://emissive_from_base.fakeinterior.ron#emissive")
+    );
+    let images = app.world().resource::<Assets<Image>>();
+    let mask = images.get(&emissive.unwrap()).unwrap();
+    // the dark wall stays dark, the bright window glows.
+    assert_eq!(mask.get_color_at(0, 0).unwrap(), Color::srgb_u8(0, 0, 0));
+    assert_eq!(
+        mask.get_color_at(1, 0).unwrap(),
+        Color::srgb_u8(250, 240, 200)
+    );
+}
+
+#[test]
+fn unknown_field_fails() {
+    let mut app = app();
+    let (_, state) = load(&mut app, "loader://unknown_field.fakeinterior.ron");
+    let LoadState::Failed(err) = state else {
+        panic!("loaded a description with an unknown field");
+    };
+    assert!(err.to_string().contains("room_count"), "{err}");
+}
+
+#[test]
+fn from_paths_creates_handles() {
+    let app = app();
+    let asset_server = app.world().resource::<AssetServer>();
+    let material = FakeInteriorMaterial::from_paths(
+        asset_server,
+        "textures/rooms_depth.png",
+        "textures/rooms_emit.png",
+        FakeInteriorMaterial {
+            depth: 0.3,
+            ..default()
+        },
+    );
+    assert_eq!(
+        texture_path(&material.base.base_color_texture).as_deref(),
+        Some("textures/rooms_depth.png")
+    );
+    assert_eq!(
+        texture_path(&material.base.emissive_texture).as_deref(),
+        Some("textures/rooms_emit.png")
+    );
+    assert_eq!(material.base.emissive, LinearRgba::WHITE);
+    assert_eq!(material.extension.depth, 0.3);
+}
+
+#[test]
+fn placeholder_fills_missing_atlas() {
+    let mut app = app_with(FakeInteriorMaterialPlugin {
+        use_placeholder: true,
+        ..default()
+    });
+    let mut materials = app
+        .world_mut()
+        .resource_mut::<Assets<StandardFakeInteriorMaterial>>();
+    let bare = materials.add(StandardFakeInteriorMaterial::default());
+    let textured = materials.add(StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(Handle::weak_from_u128(1)),
+            ..default()
+        },
+        ..default()
+    });
+    // the `Added` events are sent at the end of the first update.
+    app.update();
+    app.update();
+
+    let materials = app
+        .world()
+        .resource::<Assets<StandardFakeInteriorMaterial>>();
+    assert_eq!(
+        materials.get(&bare).unwrap().base.base_color_texture,
+        Some(FAKE_INTERIOR_PLACEHOLDER_HANDLE)
+    );
+    assert_eq!(
+        materials.get(&textured).unwrap().base.base_color_texture,
+        Some(Handle::weak_from_u128(1))
+    );
+    let images = app.world().resource::<Assets<Image>>();
+    assert!(images.contains(&FAKE_INTERIOR_PLACEHOLDER_HANDLE));
+}
+
+#[test]
+fn ready_once_textures_load() {
+    let mut app = app();
+    let asset_server = app.world().resource::<AssetServer>();
+    let material = FakeInteriorMaterial::from_paths(
+        asset_server,
+        "loader://room.png",
+        "loader://room.png",
+        default(),
+    );
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<StandardFakeInteriorMaterial>>()
+        .add(material);
+    let entity = app.world_mut().spawn(MeshMaterial3d(material.clone())).id();
+
+    // the events may stay for a few frames, read each one once.
+    let mut cursor = app
+        .world()
+        .resource::<Events<FakeInteriorReady>>()
+        .get_cursor();
+    let mut sent = Vec::new();
+    for _ in 0..MAX_FRAMES {
+        app.update();
+        let events = app.world().resource::<Events<FakeInteriorReady>>();
+        sent.extend(cursor.read(events).copied());
+        if !sent.is_empty() {
+            break;
+        }
+    }
+    assert_eq!(
+        sent,
+        [FakeInteriorReady {
+            entity,
+            asset_id: material.id(),
+        }]
+    );
+    let asset_server = app.world().resource::<AssetServer>();
+    assert!(asset_server.is_loaded(&asset_server.load::<Image>("loader://room.png")));
+
+    // only sent once.
+    for _ in 0..3 {
+        app.update();
+    }
+    let events = app.world().resource::<Events<FakeInteriorReady>>();
+    assert!(cursor.read(events).next().is_none());
+}
@@ -0,0 +1,59 @@
+//! Round trips of `FakeInteriorParams` through serde.
+
+use bevy::{asset::ron, prelude::*};
+
+use bevy_fake_interior::*;
+
+#[test]
+fn params_round_trip() {
+    let params = FakeInteriorParams {
+        atlas_rooms: Vec2::new(3.0, 2.0),
+        rooms: Vec2::new(6.0, 4.0),
+        depth: 0.35,
+        room_seed: 1.2,
+        emission_seed: 4.5,
+        flicker_seed: 2.5,
+        emission_threshold: 0.7,
+        emission_intensity: 12.0,
+        uv_tiling: Vec2::new(2.0, 1.0),
+    };
+    let text = ron::to_string(&params).unwrap();
+    let read: FakeInteriorParams = ron::from_str(&text).unwrap();
+    assert_eq!(read, params);
+
+    // through a material and back.
+    let material = FakeInteriorMaterial::from(read);
+    assert_eq!(FakeInteriorParams::from(&material), params);
+}
+
+#[test]
+fn missing_params_are_defaults() {
+    let read: FakeInteriorParams = ron::from_str("(depth: 0.25)").unwrap();
+    assert_eq!(
+        read,
+        FakeInteriorParams {
+            depth: 0.25,
+            ..default()
+        }
+    );
+    assert_eq!(read.rooms, FakeInteriorMaterial::default().rooms);
+}
+
+#[test]
+fn description_reads_the_params() {
+    let desc: FakeInteriorDescription =
+        ron::from_str("(rooms: (6.0, 4.0), flicker_seed: 2.5, uv_tiling: (2.0, 1.0))").unwrap();
+    assert_eq!(
+        desc.params(),
+        FakeInteriorParams {
+            rooms: Vec2::new(6.0, 4.0),
+            flicker_seed: 2.5,
+            uv_tiling: Vec2::new(2.0, 1.0),
+            ..default()
+        }
+    );
+    assert_eq!(
+        FakeInteriorDescription::default().params(),
+        FakeInteriorParams::default()
+    );
+}
@@ -0,0 +1,535 @@
+//! Adds the `FakeInteriorMaterialPlugin` to an app and checks the resources and systems it adds.
+//! Doesn't need a GPU.
+
+use bevy::{
+    image::{ImageAddressMode, ImageFilterMode, ImageSampler},
+    log::LogPlugin,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureViewDimension},
+        settings::WgpuSettings,
+        RenderPlugin,
+    },
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+
+use bevy_fake_interior::*;
+
+fn app() -> App {
+    app_with_images(ImagePlugin::default())
+}
+
+fn app_with_images(images: ImagePlugin) -> App {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(images)
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            })
+            .disable::<WinitPlugin>()
+            .disable::<LogPlugin>(),
+    );
+    app
+}
+
+#[test]
+fn plugin_added_twice_is_ignored() {
+    let mut app = app();
+    app.add_plugins(FakeInteriorMaterialPlugin::default());
+    // a second plugin group adding it with other settings.
+    app.add_plugins(FakeInteriorMaterialPlugin {
+        prepass_enabled: false,
+        ..default()
+    });
+    app.finish();
+    app.cleanup();
+    app.update();
+
+    // only the first one added the material.
+    let material_plugins = app.get_added_plugins::<MaterialPlugin<StandardFakeInteriorMaterial>>();
+    assert_eq!(material_plugins.len(), 1);
+    assert!(material_plugins[0].prepass_enabled);
+}
+
+/// 2x2 room image of a single color.
+fn room_image(color: [u8; 4]) -> Image {
+    Image::new_fill(
+        Extent3d {
+            width: 2,
+            height: 2,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &color,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+#[test]
+fn room_image_arrays_stack_the_rooms() {
+    let mut app = app();
+    app.add_plugins(FakeInteriorMaterialPlugin::default());
+    app.finish();
+    app.cleanup();
+
+    let world = app.world_mut();
+    let mut images = world.resource_mut::<Assets<Image>>();
+    let red = images.add(room_image([255, 0, 0, 255]));
+    let blue = images.add(room_image([0, 0, 255, 255]));
+    let green = images.add(room_image([0, 255, 0, 255]));
+    let array = world.resource_scope(|world, mut arrays: Mut<RoomImageArrays>| {
+        let mut images = world.resource_mut::<Assets<Image>>();
+        arrays.add(&mut images, vec![red.clone(), blue])
+    });
+    app.update();
+
+    // first texel of each layer.
+    let layers = |app: &App| {
+        let image = app.world().resource::<Assets<Image>>().get(&array).unwrap();
+        assert_eq!(
+            image.texture_view_descriptor.as_ref().unwrap().dimension,
+            Some(TextureViewDimension::D2Array)
+        );
+        image
+            .data
+            .chunks(2 * 2 * 4)
+            .map(|layer| [layer[0], layer[1], layer[2], layer[3]])
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(layers(&app), [[255, 0, 0, 255], [0, 0, 255, 255]]);
+
+    let mut arrays = app.world_mut().resource_mut::<RoomImageArrays>();
+    arrays.remove(&array, &red);
+    arrays.push(&array, green);
+    app.update();
+    assert_eq!(layers(&app), [[0, 0, 255, 255], [0, 255, 0, 255]]);
+}
+
+#[test]
+fn lod_camera_flattens_far_walls() {
+    let mut app = app();
+    app.add_plugins(FakeInteriorMaterialPlugin {
+        use_placeholder: false,
+        ..default()
+    });
+    app.finish();
+    app.cleanup();
+
+    let world = app.world_mut();
+    let material = world
+        .resource_mut::<Assets<StandardFakeInteriorMaterial>>()
+        .add(StandardFakeInteriorMaterial::default());
+    let near = world
+        .spawn((MeshMaterial3d(material.clone()), Transform::IDENTITY))
+        .id();
+    let far = world
+        .spawn((
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(0.0, 0.0, -500.0),
+        ))
+        .id();
+    // a floor below the camera, grazing for a wall facing +Z.
+    let floor = world
+        .spawn((
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(0.0, -20.0, 9.0),
+            FakeInteriorLodFacing(Dir3::Y),
+        ))
+        .id();
+    let camera = world
+        .spawn((
+            FakeInteriorLodCamera::default(),
+            Transform::from_xyz(0.0, 0.0, 10.0),
+        ))
+        .id();
+    app.update();
+
+    let quality = |app: &App, entity: Entity| {
+        let handle = app
+            .world()
+            .get::<MeshMaterial3d<StandardFakeInteriorMaterial>>(entity)
+            .unwrap();
+        let materials = app
+            .world()
+            .resource::<Assets<StandardFakeInteriorMaterial>>();
+        (
+            handle.0 == material,
+            materials.get(handle).unwrap().extension.quality,
+        )
+    };
+    assert_eq!(quality(&app, near), (true, QualityTier::High));
+    assert_eq!(quality(&app, far), (false, QualityTier::Low));
+    assert_eq!(quality(&app, floor), (true, QualityTier::High));
+
+    // next to the far wall, the near one is behind the camera's side of the wall now.
+    app.world_mut()
+        .entity_mut(camera)
+        .insert(Transform::from_xyz(0.0, 0.0, -490.0));
+    app.update();
+    assert_eq!(quality(&app, near), (false, QualityTier::Low));
+    assert_eq!(quality(&app, far), (true, QualityTier::High));
+
+    // changes of the material reach the flat copy.
+    app.world_mut()
+        .resource_mut::<Assets<StandardFakeInteriorMaterial>>()
+        .get_mut(&material)
+        .unwrap()
+        .extension
+        .depth = 0.25;
+    app.update();
+    app.update();
+    let handle = app
+        .world()
+        .get::<MeshMaterial3d<StandardFakeInteriorMaterial>>(near)
+        .unwrap();
+    let materials = app
+        .world()
+        .resource::<Assets<StandardFakeInteriorMaterial>>();
+    assert_eq!(materials.get(handle).unwrap().extension.depth, 0.25);
+
+    // without a camera every wall gets its material back.
+    app.world_mut().despawn(camera);
+    app.update();
+    assert_eq!(quality(&app, near), (true, QualityTier::High));
+    assert_eq!(quality(&app, far), (true, QualityTier::High));
+}
+
+#[test]
+fn lod_leaves_walls_alone_without_camera() {
+    let mut app = app();
+    app.add_plugins(FakeInteriorMaterialPlugin {
+        use_placeholder: false,
+        ..default()
+    });
+    app.finish();
+    app.cleanup();
+
+    let world = app.world_mut();
+    let material = world
+        .resource_mut::<Assets<StandardFakeInteriorMaterial>>()
+        .add(StandardFakeInteriorMaterial::default());
+    let wall = world
+        .spawn((
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(0.0, 0.0, -500.0),
+        ))
+        .id();
+    app.update();
+    app.update();
+
+    let world = app.world();
+    assert_eq!(
+        world
+            .get::<MeshMaterial3d<StandardFakeInteriorMaterial>>(wall)
+            .unwrap()
+            .0,
+        material
+    );
+    assert!(!world
+        .inspect_entity(wall)
+        .any(|component| component.name().contains("lod::")));
+    assert_eq!(
+        world
+            .resource::<Assets<StandardFakeInteriorMaterial>>()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn fake_interior_from_keeps_the_standard_material() {
+    let mut app = app();
+    app.add_plugins(FakeInteriorMaterialPlugin {
+        use_placeholder: false,
+        ..default()
+    });
+    app.finish();
+    app.cleanup();
+
+    let world = app.world_mut();
+    let atlas = world
+        .resource_mut::<Assets<Image>>()
+        .add(room_image([255, 0, 0, 255]));
+    // still loading.
+    let source = world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .reserve_handle();
+    let wall = world
+        .spawn((
+            MeshMaterial3d(source.clone()),
+            FakeInteriorFrom(
+                source.clone(),
+                FakeInteriorMaterial {
+                    depth: 0.3,
+                    ..default()
+                },
+            ),
+        ))
+        .id();
+    app.update();
+    assert!(app.world().get::<FakeInteriorFrom>(wall).is_some());
+    assert!(app
+        .world()
+        .get::<MeshMaterial3d<StandardFakeInteriorMaterial>>(wall)
+        .is_none());
+
+    app.world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .insert(
+            &source,
+            StandardMaterial {
+                base_color_texture: Some(atlas.clone()),
+                perceptual_roughness: 0.2,
+                ..default()
+            },
+        );
+    app.update();
+    let world = app.world();
+    assert!(world.get::<FakeInteriorFrom>(wall).is_none());
+    assert!(world
+        .get::<MeshMaterial3d<StandardMaterial>>(wall)
+        .is_none());
+    let interior = world
+        .get::<MeshMaterial3d<StandardFakeInteriorMaterial>>(wall)
+        .unwrap();
+    let interior = world
+        .resource::<Assets<StandardFakeInteriorMaterial>>()
+        .get(interior)
+        .unwrap();
+    assert_eq!(interior.base.base_color_texture, Some(atlas));
+    assert_eq!(interior.base.perceptual_roughness, 0.2);
+    assert_eq!(interior.extension.depth, 0.3);
+}
+
+#[cfg(feature = "instance_params")]
+#[test]
+fn instances_sharing_a_translation_keep_the_table_small() {
+    use bevy::render::storage::ShaderStorageBuffer;
+
+    let mut app = app();
+    app.add_plugins(FakeInteriorMaterialPlugin::default());
+    app.finish();
+    app.cleanup();
+
+    // more than the shader probes, all on one translation, and one elsewhere.
+    for _ in 0..12 {
+        app.world_mut().spawn((
+            FakeInteriorOverride {
+                depth: Some(0.25),
+                ..default()
+            },
+            Transform::from_xyz(1.0, 2.0, 3.0),
+        ));
+    }
+    app.world_mut()
+        .spawn((FakeInteriorPower(false), Transform::from_xyz(4.0, 2.0, 3.0)));
+    app.update();
+
+    assert_eq!(app.world().resource::<FakeInteriorInstances>().len(), 13);
+    let buffers = app.world().resource::<Assets<ShaderStorageBuffer>>();
+    let table = buffers.get(&FAKE_INTERIOR_INSTANCES_HANDLE).unwrap();
+    // two kept entries, in a table of twice their count with 48 byte entries.
+    assert_eq!(table.data.as_ref().unwrap().len(), 4 * 48);
+}
+
+#[cfg(feature = "instance_params")]
+#[test]
+fn instance_table_changes_rebind_only_its_materials() {
+    use bevy::render::storage::ShaderStorageBuffer;
+
+    let mut app = app();
+    app.add_plugins(FakeInteriorMaterialPlugin {
+        use_placeholder: false,
+        ..default()
+    });
+    app.finish();
+    app.cleanup();
+
+    let world = app.world_mut();
+    let bound = world
+        .resource_mut::<Assets<StandardFakeInteriorMaterial>>()
+        .add(StandardFakeInteriorMaterial::default());
+    let own_buffer = world
+        .resource_mut::<Assets<ShaderStorageBuffer>>()
+        .add(ShaderStorageBuffer::default());
+    let mut unbound = StandardFakeInteriorMaterial::default();
+    unbound.extension.instances = own_buffer;
+    let unbound = world
+        .resource_mut::<Assets<StandardFakeInteriorMaterial>>()
+        .add(unbound);
+    let wall = world
+        .spawn((FakeInteriorOverride::default(), Transform::IDENTITY))
+        .id();
+    app.update();
+    app.update();
+
+    let modified_after = |app: &mut App, depth: f32| {
+        let events = app
+            .world()
+            .resource::<Events<AssetEvent<StandardFakeInteriorMaterial>>>();
+        let mut cursor = events.get_cursor_current();
+        app.world_mut()
+            .entity_mut(wall)
+            .insert(FakeInteriorOverride {
+                depth: Some(depth),
+                ..default()
+            });
+        app.update();
+        let events = app
+            .world()
+            .resource::<Events<AssetEvent<StandardFakeInteriorMaterial>>>();
+        cursor
+            .read(events)
+            .filter_map(|event| match event {
+                AssetEvent::Modified { id } => Some(*id),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(modified_after(&mut app, 0.25), vec![bound.id()]);
+    // the same table again isn't uploaded.
+    assert!(modified_after(&mut app, 0.25).is_empty());
+    assert!(!modified_after(&mut app, 0.5).contains(&unbound.id()));
+}
+
+#[cfg(feature = "instance_params")]
+#[test]
+fn fake_interior_power_merges_with_overrides() {
+    let mut app = app();
+    app.add_plugins(FakeInteriorMaterialPlugin::default());
+    app.finish();
+    app.cleanup();
+
+    let dark = app.world_mut().spawn(FakeInteriorPower(false)).id();
+    let overridden = app
+        .world_mut()
+        .spawn((
+            FakeInteriorOverride {
+                depth: Some(0.25),
+                ..default()
+            },
+            FakeInteriorPower(false),
+        ))
+        .id();
+    app.update();
+    let instances = app.world().resource::<FakeInteriorInstances>();
+    assert!(!instances.get(dark).unwrap().powered);
+    let instance = instances.get(overridden).unwrap();
+    assert!(!instance.powered);
+    assert_eq!(instance.depth, Some(0.25));
+
+    // back on, then only the override is left.
+    app.world_mut()
+        .entity_mut(dark)
+        .insert(FakeInteriorPower(true));
+    app.world_mut()
+        .entity_mut(overridden)
+        .remove::<FakeInteriorPower>();
+    app.update();
+    let instances = app.world().resource::<FakeInteriorInstances>();
+    assert!(instances.get(dark).unwrap().powered);
+    let instance = instances.get(overridden).unwrap();
+    assert!(instance.powered);
+    assert_eq!(instance.depth, Some(0.25));
+
+    app.world_mut()
+        .entity_mut(dark)
+        .remove::<FakeInteriorPower>();
+    app.update();
+    assert!(app
+        .world()
+        .resource::<FakeInteriorInstances>()
+        .get(dark)
+        .is_none());
+}
+
+#[cfg(feature = "diagnostics")]
+#[test]
+fn diagnostics_count_visible_walls() {
+    let mut app = app();
+    app.add_plugins(FakeInteriorMaterialPlugin::default());
+    app.finish();
+    app.cleanup();
+
+    let mesh = app
+        .world_mut()
+        .resource_mut::<Assets<Mesh>>()
+        .add(fake_interior_wall_mesh(Vec2::ONE));
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<StandardFakeInteriorMaterial>>()
+        .add(StandardFakeInteriorMaterial::default());
+    app.world_mut().spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    for x in [0.0, 100.0] {
+        app.world_mut().spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(x, 0.0, 0.0),
+        ));
+    }
+    app.update();
+    app.update();
+
+    // the wall far to the side is out of view.
+    let store = app.world().resource::<bevy::diagnostic::DiagnosticsStore>();
+    let visible = store.get(&FakeInteriorDiagnostics::VISIBLE_WALLS).unwrap();
+    assert_eq!(visible.value(), Some(1.0));
+}
+
+#[test]
+fn atlas_address_mode_keeps_default_filtering() {
+    let mut app = app_with_images(ImagePlugin::default_nearest());
+    app.add_plugins(FakeInteriorMaterialPlugin::default());
+    app.finish();
+    app.cleanup();
+
+    let atlas = app
+        .world_mut()
+        .resource_mut::<Assets<Image>>()
+        .add(Image::default());
+    let _material = app
+        .world_mut()
+        .resource_mut::<Assets<StandardFakeInteriorMaterial>>()
+        .add(StandardFakeInteriorMaterial {
+            base: StandardMaterial {
+                base_color_texture: Some(atlas.clone()),
+                ..default()
+            },
+            extension: FakeInteriorMaterial {
+                atlas_address_mode_u: AtlasAddressMode::Repeat,
+                ..default()
+            },
+        });
+    // the asset events are sent at the end of the first update.
+    app.update();
+    app.update();
+
+    // the app's nearest filtering stays, only the address mode changes.
+    let images = app.world().resource::<Assets<Image>>();
+    let ImageSampler::Descriptor(sampler) = &images.get(&atlas).unwrap().sampler else {
+        panic!("the atlas kept the default sampler");
+    };
+    assert!(matches!(sampler.mag_filter, ImageFilterMode::Nearest));
+    assert!(matches!(sampler.address_mode_u, ImageAddressMode::Repeat));
+    assert!(matches!(
+        sampler.address_mode_v,
+        ImageAddressMode::ClampToEdge
+    ));
+}
@@ -0,0 +1,327 @@
+//! Renders a fixed fake interior scene offscreen and compares it against a reference image,
+//! against the same scene with a wall mesh whose tangents are zeroed, and against the scene
+//! mirrored with a negative scale.
+//!
+//! This needs a GPU, so it's ignored by default. Run it with:
+//!
+//! `cargo test --test render_reference -- --ignored`
+//!
+//! Set `FAKE_INTERIOR_BLESS=1` to (re)write `tests/reference/fake_interior.png` after an
+//! intended visual change. On GL backends, e.g. `WGPU_BACKEND=gl` with Mesa's llvmpipe, build
+//! it with `--no-default-features`: the `extra_textures` don't fit in their texture slots.
+
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    app::PluginsState,
+    asset::{io::AssetSource, AssetMetaCheck, LoadState},
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        mesh::*,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::screenshot::{Screenshot, ScreenshotCaptured},
+    },
+    tasks::tick_global_task_pools_on_main_thread,
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+
+use bevy_fake_interior::*;
+
+const SIZE: u32 = 256;
+const REFERENCE: &str = "tests/reference/fake_interior.png";
+/// Frames to render after the textures are loaded, so the pipelines are compiled.
+const WARMUP_FRAMES: u32 = 60;
+const MAX_FRAMES: u32 = 1000;
+/// Max difference of a color channel before a pixel counts as different.
+const CHANNEL_TOLERANCE: u8 = 8;
+/// Fraction of pixels allowed to differ.
+const MAX_DIFFERENT_PIXELS: f32 = 0.005;
+
+#[derive(Resource)]
+struct Target(Handle<Image>);
+
+/// Mesh of the wall in the scene, and the scale that mirrors the whole scene.
+#[derive(Resource)]
+struct Scene {
+    wall: Mesh,
+    mirror: Vec3,
+}
+
+fn wall_mesh() -> Mesh {
+    PlaneMeshBuilder::from_length(2.0)
+        .build()
+        .with_generated_tangents()
+        .unwrap()
+}
+
+fn app(wall: Mesh, mirror: Vec3) -> App {
+    let mut app = App::new();
+
+    app.register_asset_source(
+        "reference",
+        AssetSource::build().with_reader(AssetSource::get_default_reader("tests/reference".into())),
+    );
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            // the `.meta` files of the textures are for the examples' asset processor, load
+            // the PNGs as they are.
+            .set(AssetPlugin {
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            })
+            .disable::<WinitPlugin>(),
+    );
+    app.add_plugins(FakeInteriorMaterialPlugin::default())
+        .insert_resource(Scene { wall, mirror })
+        .add_systems(Startup, setup);
+
+    // wait for the render device before finishing the plugins.
+    while app.plugins_state() != PluginsState::Ready {
+        tick_global_task_pools_on_main_thread();
+    }
+    app.finish();
+    app.cleanup();
+    app
+}
+
+/// set up a fixed scene rendered into `Target`
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut interiors: ResMut<Assets<StandardFakeInteriorMaterial>>,
+    asset_server: Res<AssetServer>,
+    scene: Res<Scene>,
+) {
+    let mut target = Image::new_fill(
+        Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    target.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_DST
+        | TextureUsages::COPY_SRC
+        | TextureUsages::RENDER_ATTACHMENT;
+    let target = images.add(target);
+    commands.insert_resource(Target(target.clone()));
+
+    let interior = interiors.add(StandardFakeInteriorMaterial {
+        base: StandardMaterial {
+            base_color_texture: Some(asset_server.load("textures/rooms_depth.png")),
+            emissive: LinearRgba::WHITE,
+            emissive_texture: Some(asset_server.load("textures/rooms_emit.png")),
+            reflectance: 1.0,
+            // mirroring turns the triangles' winding around and Bevy doesn't flip the culling.
+            cull_mode: None,
+            ..default()
+        },
+        extension: FakeInteriorMaterial {
+            atlas_rooms: Vec2::new(3.0, 2.0),
+            rooms: Vec2::new(4.0, 4.0),
+            depth: 0.5,
+            room_seed: 1.2,
+            emission_intensity: 10.0,
+            ..default()
+        },
+    });
+
+    // wall
+    commands.spawn((
+        Mesh3d(meshes.add(scene.wall.clone())),
+        MeshMaterial3d(interior),
+        Transform::from_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2))
+            .with_scale(scene.mirror),
+    ));
+
+    // light
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 2000.0,
+            ..default()
+        },
+        Transform::from_translation(Vec3::new(2.0, 4.0, 4.0) * scene.mirror)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // camera, off-axis to show the parallax of the rooms.
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            target: RenderTarget::Image(target),
+            ..default()
+        },
+        Transform::from_translation(Vec3::new(0.6, 0.4, 2.5) * scene.mirror)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+/// Update `app` until `done` returns true.
+fn update_until(app: &mut App, what: &str, mut done: impl FnMut(&mut App) -> bool) {
+    for _ in 0..MAX_FRAMES {
+        app.update();
+        if done(app) {
+            return;
+        }
+    }
+    panic!("timed out waiting for {what}");
+}
+
+/// Render the scene once its textures are loaded.
+fn render(app: &mut App) -> Image {
+    update_until(app, "the room textures", |app| {
+        let asset_server = app.world().resource::<AssetServer>();
+        let materials = app
+            .world()
+            .resource::<Assets<StandardFakeInteriorMaterial>>();
+        materials.iter().all(|(_, material)| {
+            [
+                &material.base.base_color_texture,
+                &material.base.emissive_texture,
+            ]
+            .into_iter()
+            .flatten()
+            .all(|texture| asset_server.is_loaded_with_dependencies(texture))
+        })
+    });
+    for _ in 0..WARMUP_FRAMES {
+        app.update();
+    }
+
+    let captured = Arc::new(Mutex::new(None));
+    let target = app.world().resource::<Target>().0.clone();
+    let sink = captured.clone();
+    app.world_mut().spawn(Screenshot::image(target)).observe(
+        move |trigger: Trigger<ScreenshotCaptured>| {
+            *sink.lock().unwrap() = Some(trigger.event().0.clone());
+        },
+    );
+    update_until(app, "the screenshot", |_| {
+        captured.lock().unwrap().is_some()
+    });
+    let rendered = captured.lock().unwrap().take().unwrap();
+    rendered
+}
+
+/// Number of pixels of `a` and `b` with a color channel differing by more than
+/// `CHANNEL_TOLERANCE`, both are RGBA8.
+fn different_pixels(a: &Image, b: &Image) -> usize {
+    a.data
+        .chunks_exact(4)
+        .zip(b.data.chunks_exact(4))
+        .filter(|(a, b)| {
+            a.iter()
+                .zip(b.iter())
+                .take(3)
+                .any(|(a, b)| a.abs_diff(*b) > CHANNEL_TOLERANCE)
+        })
+        .count()
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn render_reference() {
+    let mut app = app(wall_mesh(), Vec3::ONE);
+    let rendered = render(&mut app);
+
+    if std::env::var_os("FAKE_INTERIOR_BLESS").is_some() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(REFERENCE);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        rendered
+            .try_into_dynamic()
+            .unwrap()
+            .to_rgb8()
+            .save(&path)
+            .unwrap();
+        println!("wrote {}", path.display());
+        return;
+    }
+
+    let reference: Handle<Image> = app
+        .world()
+        .resource::<AssetServer>()
+        .load("reference://fake_interior.png");
+    update_until(&mut app, "the reference image", |app| {
+        match app.world().resource::<AssetServer>().load_state(&reference) {
+            LoadState::Loaded => true,
+            LoadState::Failed(err) => {
+                panic!(
+                    "can't load {REFERENCE} ({err}), run with FAKE_INTERIOR_BLESS=1 to create it"
+                )
+            }
+            _ => false,
+        }
+    });
+    let reference = app
+        .world()
+        .resource::<Assets<Image>>()
+        .get(&reference)
+        .unwrap();
+
+    assert_eq!(reference.size(), rendered.size(), "reference size");
+    // the reference is saved without alpha.
+    let pixels = (SIZE * SIZE) as usize;
+    let different = different_pixels(reference, &rendered);
+    assert!(
+        different as f32 <= pixels as f32 * MAX_DIFFERENT_PIXELS,
+        "{different} of {pixels} pixels differ from {REFERENCE}"
+    );
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn zeroed_tangents_render_like_generated_ones() {
+    let expected = render(&mut app(wall_mesh(), Vec3::ONE));
+
+    // some imported meshes have a tangent attribute full of zeros.
+    let mut wall = wall_mesh();
+    let vertices = wall.count_vertices();
+    wall.insert_attribute(Mesh::ATTRIBUTE_TANGENT, vec![[0.0f32; 4]; vertices]);
+    let rendered = render(&mut app(wall, Vec3::ONE));
+
+    let pixels = (SIZE * SIZE) as usize;
+    let different = different_pixels(&expected, &rendered);
+    assert!(
+        different as f32 <= pixels as f32 * MAX_DIFFERENT_PIXELS,
+        "{different} of {pixels} pixels differ with zeroed tangents"
+    );
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn mirrored_wall_renders_mirrored() {
+    let expected = render(&mut app(wall_mesh(), Vec3::ONE));
+    // the wall, light and camera mirrored along x.
+    let mut rendered = render(&mut app(wall_mesh(), Vec3::new(-1.0, 1.0, 1.0)));
+
+    // flip the image back.
+    let row = SIZE as usize * 4;
+    for line in rendered.data.chunks_exact_mut(row) {
+        let pixels: Vec<[u8; 4]> = line
+            .chunks_exact(4)
+            .rev()
+            .map(|pixel| pixel.try_into().unwrap())
+            .collect();
+        line.copy_from_slice(pixels.as_flattened());
+    }
+
+    let pixels = (SIZE * SIZE) as usize;
+    let different = different_pixels(&expected, &rendered);
+    assert!(
+        different as f32 <= pixels as f32 * MAX_DIFFERENT_PIXELS,
+        "{different} of {pixels} pixels differ in the mirrored scene"
+    );
+}
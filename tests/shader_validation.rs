@@ -0,0 +1,504 @@
+//! Composes `fake_interior.wgsl` with Bevy's shader imports and validates it with naga's
+//! strictest validation for the common pipeline configurations, and checks its depth
+//! output, its bindings and the uniform layout against the Rust side. Doesn't need a GPU.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    log::LogPlugin,
+    prelude::*,
+    reflect::{ReflectMut, ReflectRef},
+    render::{
+        render_resource::{
+            encase::UniformBuffer, Shader, ShaderDefVal, ShaderImport, ShaderType, Source,
+        },
+        settings::WgpuSettings,
+        RenderPlugin,
+    },
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use naga::{
+    valid::{Capabilities, ValidationFlags, Validator},
+    Binding, BuiltIn, Module, TypeInner,
+};
+use naga_oil::compose::{
+    ComposableModuleDescriptor, Composer, NagaModuleDescriptor, ShaderDefValue,
+};
+
+use bevy_fake_interior::*;
+
+const SHADER: &str = "assets/shaders/fake_interior.wgsl";
+
+/// Shader defs of the mesh pipelines that are always set for a textured, tangent mesh.
+const MESH_DEFS: &[&str] = &[
+    "VERTEX_OUTPUT_INSTANCE_INDEX",
+    "VERTEX_POSITIONS",
+    "VERTEX_NORMALS",
+    "VERTEX_UVS",
+    "VERTEX_UVS_A",
+    "VERTEX_TANGENTS",
+];
+
+/// Shader defs of all the optional textures and their bindings.
+const ALL_TEXTURES: &[&str] = &[
+    "FAKE_INTERIOR_OCCUPANTS",
+    "FAKE_INTERIOR_LIGHT_PALETTE",
+    "FAKE_INTERIOR_MULLION_MASK",
+    "FAKE_INTERIOR_ROOM_IMAGES",
+    "FAKE_INTERIOR_ROOM_INDEX_MAP",
+    "FAKE_INTERIOR_PLANAR_REFLECTION",
+    "FAKE_INTERIOR_EMISSIVE_LAYERS",
+];
+
+/// Pipeline configurations the material is specialized for.
+const CONFIGS: &[(&str, &[&str])] = &[
+    ("forward", &[]),
+    (
+        "forward, normal map and vertex colors",
+        &["STANDARD_MATERIAL_NORMAL_MAP", "VERTEX_COLORS"],
+    ),
+    (
+        "forward, instance params",
+        &["FAKE_INTERIOR_INSTANCE_PARAMS"],
+    ),
+    ("forward, no emissive", &["FAKE_INTERIOR_NO_EMISSIVE"]),
+    (
+        "forward, planar reflection",
+        &["FAKE_INTERIOR_PLANAR_REFLECTION"],
+    ),
+    ("forward, all textures", ALL_TEXTURES),
+    (
+        "forward, room images and index map",
+        &["FAKE_INTERIOR_ROOM_IMAGES", "FAKE_INTERIOR_ROOM_INDEX_MAP"],
+    ),
+    (
+        "forward, glass anisotropy",
+        &[
+            "STANDARD_MATERIAL_ANISOTROPY",
+            "FAKE_INTERIOR_GLASS_ANISOTROPY",
+        ],
+    ),
+    ("forward, medium quality", &["FAKE_INTERIOR_NO_DETAILS"]),
+    (
+        "forward, low quality",
+        &["FAKE_INTERIOR_FLAT", "FAKE_INTERIOR_NO_DETAILS"],
+    ),
+    (
+        "deferred prepass, no emissive",
+        &[
+            "PREPASS_PIPELINE",
+            "PREPASS_FRAGMENT",
+            "DEFERRED_PREPASS",
+            "NORMAL_PREPASS_OR_DEFERRED_PREPASS",
+            "MOTION_VECTOR_PREPASS_OR_DEFERRED_PREPASS",
+            "FAKE_INTERIOR_NO_EMISSIVE",
+        ],
+    ),
+    (
+        "normal prepass",
+        &[
+            "PREPASS_PIPELINE",
+            "PREPASS_FRAGMENT",
+            "NORMAL_PREPASS",
+            "NORMAL_PREPASS_OR_DEFERRED_PREPASS",
+            "STANDARD_MATERIAL_NORMAL_MAP",
+        ],
+    ),
+    (
+        "normal and motion vector prepass",
+        &[
+            "PREPASS_PIPELINE",
+            "PREPASS_FRAGMENT",
+            "NORMAL_PREPASS",
+            "NORMAL_PREPASS_OR_DEFERRED_PREPASS",
+            "MOTION_VECTOR_PREPASS",
+            "MOTION_VECTOR_PREPASS_OR_DEFERRED_PREPASS",
+            "DEPTH_CLAMP_ORTHO",
+        ],
+    ),
+    (
+        "deferred prepass",
+        &[
+            "PREPASS_PIPELINE",
+            "PREPASS_FRAGMENT",
+            "DEFERRED_PREPASS",
+            "NORMAL_PREPASS_OR_DEFERRED_PREPASS",
+            "MOTION_VECTOR_PREPASS_OR_DEFERRED_PREPASS",
+        ],
+    ),
+    (
+        "deferred prepass, all textures",
+        &[
+            "PREPASS_PIPELINE",
+            "PREPASS_FRAGMENT",
+            "DEFERRED_PREPASS",
+            "NORMAL_PREPASS_OR_DEFERRED_PREPASS",
+            "MOTION_VECTOR_PREPASS_OR_DEFERRED_PREPASS",
+            "FAKE_INTERIOR_OCCUPANTS",
+            "FAKE_INTERIOR_LIGHT_PALETTE",
+            "FAKE_INTERIOR_MULLION_MASK",
+            "FAKE_INTERIOR_ROOM_IMAGES",
+            "FAKE_INTERIOR_ROOM_INDEX_MAP",
+            "FAKE_INTERIOR_EMISSIVE_LAYERS",
+        ],
+    ),
+];
+
+/// Bevy's shader modules by import path, registered by the plugins without a GPU.
+fn bevy_shaders() -> HashMap<String, Shader> {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            })
+            .disable::<WinitPlugin>()
+            .disable::<LogPlugin>(),
+    );
+    // some shaders are only registered once the plugins finish.
+    app.finish();
+    app.world()
+        .resource::<Assets<Shader>>()
+        .iter()
+        .filter_map(|(_, shader)| match &shader.import_path {
+            ShaderImport::Custom(path) => Some((path.clone(), shader.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Module of an import, imports can name an item, e.g. `bevy_pbr::mesh_bindings::mesh`.
+fn import_module<'a>(shaders: &HashMap<String, Shader>, import: &'a str) -> &'a str {
+    let mut module = import;
+    while !shaders.contains_key(module) {
+        match module.rsplit_once("::") {
+            Some((parent, _)) => module = parent,
+            None => break,
+        }
+    }
+    module
+}
+
+fn shader_def(def: &ShaderDefVal) -> (String, ShaderDefValue) {
+    match def {
+        ShaderDefVal::Bool(name, value) => (name.clone(), ShaderDefValue::Bool(*value)),
+        ShaderDefVal::Int(name, value) => (name.clone(), ShaderDefValue::Int(*value)),
+        ShaderDefVal::UInt(name, value) => (name.clone(), ShaderDefValue::UInt(*value)),
+    }
+}
+
+/// Add `name` to the composer after the modules it imports.
+fn add_module(
+    composer: &mut Composer,
+    shaders: &HashMap<String, Shader>,
+    added: &mut HashSet<String>,
+    name: &str,
+) {
+    if !added.insert(name.to_string()) {
+        return;
+    }
+    let Some(shader) = shaders.get(name) else {
+        return;
+    };
+    for import in &shader.imports {
+        let ShaderImport::Custom(import) = import else {
+            continue;
+        };
+        add_module(composer, shaders, added, import_module(shaders, import));
+    }
+    let Source::Wgsl(source) = &shader.source else {
+        return;
+    };
+    let result = composer.add_composable_module(ComposableModuleDescriptor {
+        source,
+        file_path: &shader.path,
+        shader_defs: shader.shader_defs.iter().map(shader_def).collect(),
+        additional_imports: &shader.additional_imports,
+        ..default()
+    });
+    if let Err(err) = result {
+        panic!("{name}: {}", err.emit_to_string(composer));
+    }
+}
+
+/// The fake interior shader source and a composer with all of its imports.
+fn compose() -> (Composer, String) {
+    let shaders = bevy_shaders();
+    let mut composer = Composer::default().with_capabilities(Capabilities::all());
+    let mut added = HashSet::new();
+
+    let source =
+        std::fs::read_to_string(std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(SHADER))
+            .unwrap();
+    let (_, imports, _) = naga_oil::compose::get_preprocessor_data(&source);
+    for import in imports {
+        let module = import_module(&shaders, &import.import);
+        add_module(&mut composer, &shaders, &mut added, module);
+    }
+    (composer, source)
+}
+
+fn make_module(composer: &mut Composer, source: &str, name: &str, defs: &[&str]) -> Module {
+    let mut shader_defs: HashMap<String, ShaderDefValue> = MESH_DEFS
+        .iter()
+        .chain(defs.iter())
+        .map(|def| (def.to_string(), ShaderDefValue::Bool(true)))
+        .collect();
+    // set by Bevy's shader cache and the mesh view layout.
+    shader_defs.insert("MAX_DIRECTIONAL_LIGHTS".into(), ShaderDefValue::UInt(10));
+    shader_defs.insert("MAX_CASCADES_PER_LIGHT".into(), ShaderDefValue::UInt(4));
+    shader_defs.insert(
+        "AVAILABLE_STORAGE_BUFFER_BINDINGS".into(),
+        ShaderDefValue::UInt(8),
+    );
+
+    composer
+        .make_naga_module(NagaModuleDescriptor {
+            source,
+            file_path: SHADER,
+            shader_defs,
+            ..default()
+        })
+        .unwrap_or_else(|err| panic!("{name}: {}", err.emit_to_string(composer)))
+}
+
+#[test]
+fn fake_interior_shader_validates() {
+    let (mut composer, source) = compose();
+    for (name, defs) in CONFIGS {
+        // set by the plugin for every pipeline, but the shader also works without it.
+        let with_globals: Vec<&str> = defs
+            .iter()
+            .copied()
+            .chain(["FAKE_INTERIOR_GLOBALS"])
+            .collect();
+        let module = make_module(&mut composer, &source, name, &with_globals);
+        Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .unwrap_or_else(|err| panic!("{name}: {err:?}"));
+
+        let module = make_module(&mut composer, &source, name, defs);
+        Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .unwrap_or_else(|err| panic!("{name}, without globals: {err:?}"));
+        assert!(
+            module
+                .global_variables
+                .iter()
+                .all(|(_, var)| var.name.as_deref() != Some("globals")),
+            "{name}: uses globals without FAKE_INTERIOR_GLOBALS"
+        );
+    }
+}
+
+/// The interior is drawn at the depth of the wall's surface, the reversed-Z depth Bevy
+/// rasterizes the mesh at. Only the orthographic depth clamp writes `frag_depth`, the
+/// unclamped depth like Bevy's own prepass shader.
+#[test]
+fn depth_is_the_surface_depth() {
+    let (mut composer, source) = compose();
+    for (name, defs) in CONFIGS {
+        let module = make_module(&mut composer, &source, name, defs);
+        let fragment = module
+            .entry_points
+            .iter()
+            .find(|entry| entry.name == "fragment")
+            .unwrap();
+        let result = fragment.function.result.as_ref().unwrap();
+        let frag_depth = Some(Binding::BuiltIn(BuiltIn::FragDepth));
+        let writes_depth = match &module.types[result.ty].inner {
+            TypeInner::Struct { members, .. } => {
+                members.iter().any(|member| member.binding == frag_depth)
+            }
+            _ => result.binding == frag_depth,
+        };
+        assert_eq!(
+            writes_depth,
+            defs.contains(&"DEPTH_CLAMP_ORTHO"),
+            "{name}: frag_depth"
+        );
+    }
+}
+
+#[test]
+fn bindings_match_constants() {
+    let (mut composer, source) = compose();
+    let defs: Vec<&str> = ALL_TEXTURES
+        .iter()
+        .copied()
+        .chain(["FAKE_INTERIOR_INSTANCE_PARAMS"])
+        .collect();
+    let module = make_module(&mut composer, &source, "all bindings", &defs);
+    let bindings: HashMap<&str, u32> = module
+        .global_variables
+        .iter()
+        .filter_map(|(_, var)| {
+            let binding = var.binding.as_ref()?;
+            (binding.group == 2 && FAKE_INTERIOR_BINDINGS.contains(&binding.binding))
+                .then(|| (var.name.as_deref().unwrap(), binding.binding))
+        })
+        .collect();
+    let expected = HashMap::from([
+        ("material", FAKE_INTERIOR_UNIFORM_BINDING),
+        ("instances", FAKE_INTERIOR_INSTANCES_BINDING),
+        ("occupant_texture", FAKE_INTERIOR_OCCUPANT_TEXTURE_BINDING),
+        ("occupant_sampler", FAKE_INTERIOR_OCCUPANT_SAMPLER_BINDING),
+        ("light_palette_texture", FAKE_INTERIOR_LIGHT_PALETTE_BINDING),
+        (
+            "mullion_mask_texture",
+            FAKE_INTERIOR_MULLION_MASK_TEXTURE_BINDING,
+        ),
+        (
+            "mullion_mask_sampler",
+            FAKE_INTERIOR_MULLION_MASK_SAMPLER_BINDING,
+        ),
+        ("room_images", FAKE_INTERIOR_ROOM_IMAGES_BINDING),
+        (
+            "room_images_sampler",
+            FAKE_INTERIOR_ROOM_IMAGES_SAMPLER_BINDING,
+        ),
+        ("room_index_map", FAKE_INTERIOR_ROOM_INDEX_MAP_BINDING),
+        (
+            "planar_reflection_texture",
+            FAKE_INTERIOR_PLANAR_REFLECTION_TEXTURE_BINDING,
+        ),
+        (
+            "planar_reflection_sampler",
+            FAKE_INTERIOR_PLANAR_REFLECTION_SAMPLER_BINDING,
+        ),
+        ("emissive_layers", FAKE_INTERIOR_EMISSIVE_LAYERS_BINDING),
+        (
+            "emissive_layers_sampler",
+            FAKE_INTERIOR_EMISSIVE_LAYERS_SAMPLER_BINDING,
+        ),
+    ]);
+    assert_eq!(bindings, expected);
+
+    // without the defs only the uniform is bound.
+    let module = make_module(&mut composer, &source, "no textures", &[]);
+    let bound: Vec<&str> = module
+        .global_variables
+        .iter()
+        .filter(|(_, var)| {
+            var.binding.as_ref().is_some_and(|binding| {
+                binding.group == 2 && FAKE_INTERIOR_BINDINGS.contains(&binding.binding)
+            })
+        })
+        .map(|(_, var)| var.name.as_deref().unwrap())
+        .collect();
+    assert_eq!(bound, ["material"]);
+}
+
+/// Words of a numeric material field, `None` for the other fields.
+fn field_words(field: &dyn PartialReflect) -> Option<Vec<u32>> {
+    if let Some(v) = field.try_downcast_ref::<f32>() {
+        Some(vec![v.to_bits()])
+    } else if let Some(v) = field.try_downcast_ref::<u32>() {
+        Some(vec![*v])
+    } else if let Some(v) = field.try_downcast_ref::<Vec2>() {
+        Some(v.to_array().map(f32::to_bits).to_vec())
+    } else if let Some(v) = field.try_downcast_ref::<Vec3>() {
+        Some(v.to_array().map(f32::to_bits).to_vec())
+    } else {
+        field
+            .try_downcast_ref::<Vec4>()
+            .map(|v| v.to_array().map(f32::to_bits).to_vec())
+    }
+}
+
+/// Set every numeric field of `material` to a different value.
+fn set_unique_values(material: &mut FakeInteriorMaterial) {
+    let ReflectMut::Struct(fields) = material.reflect_mut() else {
+        unreachable!();
+    };
+    for i in 0..fields.field_len() {
+        let field = fields.field_at_mut(i).unwrap();
+        let v = i as f32 + 0.25;
+        if let Some(f) = field.try_downcast_mut::<f32>() {
+            *f = v;
+        } else if let Some(u) = field.try_downcast_mut::<u32>() {
+            *u = 1000 + i as u32;
+        } else if let Some(f) = field.try_downcast_mut::<Vec2>() {
+            *f = Vec2::new(v, v + 0.5);
+        } else if let Some(f) = field.try_downcast_mut::<Vec3>() {
+            *f = Vec3::new(v, v + 0.5, v + 0.75);
+        } else if let Some(f) = field.try_downcast_mut::<Vec4>() {
+            *f = Vec4::new(v, v + 0.5, v + 0.75, v + 0.125);
+        }
+    }
+}
+
+/// Every field of the WGSL `FakeInteriorMaterial` struct reads the value of the material
+/// field of the same name from the uniform buffer written by `ShaderType`.
+#[test]
+fn uniform_layout_matches_wgsl() {
+    let (mut composer, source) = compose();
+    let module = make_module(&mut composer, &source, "forward", &[]);
+    let (members, span) = module
+        .types
+        .iter()
+        .find_map(|(_, ty)| match &ty.inner {
+            TypeInner::Struct { members, span }
+                if ty.name.as_deref() == Some("FakeInteriorMaterial") =>
+            {
+                Some((members, *span))
+            }
+            _ => None,
+        })
+        .expect("WGSL FakeInteriorMaterial struct");
+
+    let mut material = FakeInteriorMaterial::default();
+    set_unique_values(&mut material);
+    let uniform = FakeInteriorMaterialUniform::from(&material);
+    assert_eq!(
+        FakeInteriorMaterialUniform::min_size().get(),
+        span as u64,
+        "size of FakeInteriorMaterialUniform and the WGSL struct"
+    );
+    let mut buffer = UniformBuffer::new(Vec::<u8>::new());
+    buffer.write(&uniform).unwrap();
+    let bytes = buffer.into_inner();
+    let word = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+
+    let mut seen = HashSet::new();
+    for member in members {
+        let name = member.name.as_deref().unwrap();
+        let len = match module.types[member.ty].inner {
+            TypeInner::Scalar(_) => 1,
+            TypeInner::Vector { size, .. } => size as usize,
+            ref inner => panic!("{name}: unexpected uniform type {inner:?}"),
+        };
+        let offset = member.offset as usize;
+        let words: Vec<u32> = (0..len).map(|i| word(offset + i * 4)).collect();
+        let field = material
+            .field(name)
+            .unwrap_or_else(|| panic!("{name}: not a FakeInteriorMaterial field"));
+        assert_eq!(
+            field_words(field).as_ref(),
+            Some(&words),
+            "{name} at {offset}"
+        );
+        seen.insert(name);
+    }
+
+    let ReflectRef::Struct(fields) = material.reflect_ref() else {
+        unreachable!();
+    };
+    for i in 0..fields.field_len() {
+        let name = fields.name_at(i).unwrap();
+        if field_words(fields.field_at(i).unwrap()).is_some() {
+            assert!(seen.contains(name), "{name}: missing from the WGSL struct");
+        }
+    }
+}